@@ -3,5 +3,16 @@ use wasm_bindgen::prelude::*;
 
 #[wasm_bindgen(js_name = isCourse)]
 pub fn is_course(buf: Vec<u8>) -> bool {
-    Course::deserialize(buf).is_ok()
+    check_course(buf).is_ok()
+}
+
+/// Throws with the reason why the buffer is not a valid course.
+#[wasm_bindgen(js_name = checkCourse)]
+pub fn check_course(buf: Vec<u8>) -> Result<(), JsError> {
+    let course = Course::deserialize(buf)
+        .map_err(|err| JsError::new(&format!("course could not be read: {err}")))?;
+    course.validate().map_err(|errors| {
+        let reasons: Vec<_> = errors.iter().map(ToString::to_string).collect();
+        JsError::new(&format!("course is invalid: {}", reasons.join("; ")))
+    })
 }
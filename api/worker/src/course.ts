@@ -1,7 +1,7 @@
 import { type IRequest, Router, type RouterType } from 'itty-router';
 
 import { logErrorResponse } from './helpers';
-import { isCourse } from './wasm/shrm_api_wasm';
+import { checkCourse } from './wasm/shrm_api_wasm';
 
 const router = Router({ base: '/course' });
 export { router as courseRouter };
@@ -13,21 +13,20 @@ router.post!('/check', async (req: IRequest) => {
   }
   const arrayBuffer = await req.arrayBuffer();
   const buffer = new Uint8Array(arrayBuffer);
-  const res = isCourse(buffer);
-  if (res) {
-    return new Response('', { status: 204 });
-  } else {
-    return new Response('', { status: 400 });
+  const invalidResponse = validateCourse(buffer);
+  if (invalidResponse) {
+    return invalidResponse;
   }
+  return new Response('', { status: 204 });
 }).post!('/upload', async (req: IRequest, env: Env) => {
   if (!req.arrayBuffer) {
     return new Response('', { status: 500 });
   }
   const arrayBuffer = await req.arrayBuffer();
   const buffer = new Uint8Array(arrayBuffer);
-  const isOk = isCourse(buffer);
-  if (!isOk) {
-    return new Response('', { status: 400 });
+  const invalidResponse = validateCourse(buffer);
+  if (invalidResponse) {
+    return invalidResponse;
   }
   // TODO id from session
   const id = '';
@@ -46,6 +45,15 @@ router.post!('/check', async (req: IRequest) => {
   return new Response('', { status: 501 });
 });
 
+function validateCourse(buffer: Uint8Array): Response | undefined {
+  try {
+    checkCourse(buffer);
+  } catch (err) {
+    const reason = err instanceof Error ? err.message : String(err);
+    return new Response(reason, { status: 400 });
+  }
+}
+
 export class Courses {
   private state: DurableObjectState;
   private env: Env;
@@ -3,3 +3,13 @@ use bevy::prelude::*;
 pub const NORMAL_BUTTON_COLOR: Color = Color::rgb(0.15, 0.15, 0.15);
 pub const HOVERED_BUTTON_COLOR: Color = Color::rgb(0.25, 0.25, 0.25);
 pub const SELECTED_BUTTON_COLOR: Color = Color::rgb(0.25, 0.65, 0.65);
+pub const PANEL_COLOR: Color = Color::rgba(0.05, 0.05, 0.05, 0.85);
+pub const START_MARKER_COLOR: Color = Color::rgba(0.25, 0.85, 0.35, 0.6);
+pub const SELECTION_COLOR: Color = Color::rgba(0.25, 0.65, 0.95, 0.3);
+pub const SELECTION_BLOCKED_COLOR: Color = Color::rgba(0.95, 0.25, 0.25, 0.3);
+pub const MINIMAP_EMPTY_COLOR: Color = Color::rgba(0.05, 0.05, 0.05, 0.6);
+pub const MINIMAP_GROUND_COLOR: Color = Color::rgb(0.6, 0.4, 0.2);
+pub const MINIMAP_BLOCK_COLOR: Color = Color::rgb(0.9, 0.7, 0.3);
+pub const MINIMAP_VIEWPORT_COLOR: Color = Color::rgba(1., 1., 1., 0.25);
+pub const MINIMAP_GOAL_COLOR: Color = Color::rgb(0.3, 0.85, 0.3);
+pub const MINIMAP_PLAYER_COLOR: Color = Color::rgb(0.95, 0.2, 0.2);
@@ -2,6 +2,9 @@ mod colors;
 mod z_index;
 
 pub use colors::*;
+pub use shrm_core::{
+    FIXED_GROUND_HEIGHT, MAX_COURSE_GOAL_OFFSET_X, MIN_GOAL_POS_X, START_AREA_WIDTH,
+};
 pub use z_index::*;
 
 pub const MOVE_IMPULSE_MULTIPLIER_GROUND: f32 = 0.04 * GROUND_FRICTION_MULTIPLIER * RAPIER_GRAVITY;
@@ -15,8 +18,18 @@ pub const LINVEL_CAP_RUN: f32 = 300.;
 pub const LINVEL_CAP_STOOP: f32 = 80.;
 pub const LINVEL_BUILD: f32 = 250.;
 
+/// Seconds of a step of the player simulation.
+pub const PLAYER_TIMESTEP: f32 = 1. / 60.;
+pub const PLAYER_TIMESTEP_LABEL: &str = "player_timestep";
+/// Moves between two steps that are farther than this are teleports and not interpolated.
+pub const PLAYER_INTERPOLATION_MAX_DISTANCE: f32 = 64.;
+
 pub const PLAYER_COLLIDER_BORDER_RADIUS: f32 = 0.4;
-pub const COLLIDER_MAX_TOI: f32 = 1. / 60.;
+pub const PLAYER_COLLIDER_HALF_WIDTH: f32 = 2. * 7. * 0.72;
+pub const PLAYER_COLLIDER_HALF_HEIGHT: f32 = 2. * 10. * 0.78;
+/// The collider shrinks from the top while stooping.
+pub const PLAYER_COLLIDER_HALF_HEIGHT_STOOP: f32 = 2. * 10. * 0.504;
+pub const COLLIDER_MAX_TOI: f32 = PLAYER_TIMESTEP;
 pub const COLLIDER_TOI_THRESHOLD: f32 = 12. / 60.;
 
 pub const RUN_THRESHOLD: f32 = LINVEL_CAP_RUN * 0.9;
@@ -34,6 +47,8 @@ pub const GROUND_FRICTION: f32 = GROUND_FRICTION_MULTIPLIER * 70. / RAPIER_GRAVI
 pub const GROUND_FRICTION_MIN_VEL: f32 = 1.5;
 pub const GROUND_FRICTION_STATIC_MULTIPLIER: f32 = 2.5 / 60.;
 pub const GROUND_FRICTION_KINETIC_MULTIPLIER: f32 = 0.3 / 60.;
+/// Scales the ground friction while stooping faster than `LINVEL_CAP_STOOP`.
+pub const CROUCH_SLIDE_FRICTION_MULTIPLIER: f32 = 0.3;
 pub const GROUND_PADDING: f32 = 1.1;
 
 pub const TILE_SIZE: f32 = 2.;
@@ -48,12 +63,16 @@ pub const GRID_COLLISION_PROJECTION_MULTIPLIER: f32 = 2.15;
 pub const CAMERA_SCALE: f32 = 0.7;
 pub const CAMERA_MIN_X: f32 = 1240. * 0.7 / 2.;
 pub const CAMERA_MIN_Y: f32 = 680. * 0.7 / 2.;
-
-pub const MAX_COURSE_X: i32 = 150;
-pub const MAX_COURSE_Y: i32 = 24;
-pub const MAX_COURSE_GOAL_OFFSET_X: i32 = 16;
-pub const MIN_GOAL_POS_X: i32 = 32;
-pub const MAX_GOAL_POS_X: i32 = MAX_COURSE_X - MAX_COURSE_GOAL_OFFSET_X;
+pub const CAMERA_ZOOM_MIN: f32 = 0.35;
+pub const CAMERA_ZOOM_MAX: f32 = 1.4;
+pub const CAMERA_ZOOM_STEP: f32 = 0.1;
+pub const CAMERA_EDGE_PAN_MARGIN: f32 = 8.;
+pub const CAMERA_EDGE_PAN_SPEED: f32 = 600.;
 
 pub const BELOW_SURFACE_THRESHOLD: f32 = -50.;
+pub const PLAYER_LIVES: u8 = 3;
+pub const DEATH_SEQUENCE_SECS: f32 = 2.5;
 pub const BELOW_SURFACE_RESPAWN_Y: f32 = 230.;
+
+pub const AUTOSAVE_SNAPSHOT_SECS: f32 = 1.;
+pub const AUTOSAVE_INTERVAL_SECS: f32 = 10.;
@@ -3,11 +3,14 @@
 
 pub const Z_INDEX_PLAYER: f32 = -0.4;
 pub const Z_INDEX_TILE: f32 = -0.2;
+pub const Z_INDEX_FLOATING_TILE: f32 = -0.18;
+pub const Z_INDEX_SELECTION: f32 = -0.15;
 
 pub const Z_INDEX_GOAL_L: f32 = -0.8;
 pub const Z_INDEX_GOAL: f32 = -0.5;
 pub const Z_INDEX_GOAL_R: f32 = -0.3;
 pub const Z_INDEX_GOAL_DRAG: f32 = -0.1;
+pub const Z_INDEX_START_MARKER: f32 = -0.1;
 
 pub const Z_INDEX_BACKGROUND: f32 = -1.;
 pub const Z_INDEX_GRID: f32 = -0.05;
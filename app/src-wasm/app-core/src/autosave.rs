@@ -0,0 +1,117 @@
+use crate::{now_millis, CourseRes, PlaySession, TilesLifted};
+use app_config::*;
+use bevy::prelude::*;
+use shrm_core::Course;
+use std::{sync::Mutex, time::Duration};
+use web_sys::Storage;
+
+const AUTOSAVE_KEY: &str = "shroom-kingdom-autosave";
+
+/// The course serialized after its latest change, kept outside of the world
+/// so that the panic hook can still save it.
+static SNAPSHOT: Mutex<Option<String>> = Mutex::new(None);
+
+#[derive(Resource)]
+pub(crate) struct Autosave {
+    is_dirty: bool,
+    is_saved: bool,
+    snapshot_timer: Timer,
+    save_timer: Timer,
+}
+
+impl Default for Autosave {
+    fn default() -> Self {
+        Self {
+            is_dirty: false,
+            is_saved: true,
+            snapshot_timer: Timer::new(
+                Duration::from_secs_f32(AUTOSAVE_SNAPSHOT_SECS),
+                TimerMode::Once,
+            ),
+            save_timer: Timer::new(
+                Duration::from_secs_f32(AUTOSAVE_INTERVAL_SECS),
+                TimerMode::Once,
+            ),
+        }
+    }
+}
+
+/// Serializes the course at most once a second while it changes
+/// and writes the snapshot to the browser storage every few seconds.
+pub(crate) fn autosave(
+    time: Res<Time>,
+    course: Res<CourseRes>,
+    play_session: Option<Res<PlaySession>>,
+    tiles_lifted: Res<TilesLifted>,
+    mut autosave: ResMut<Autosave>,
+) {
+    // a freshly built or loaded course must not overwrite the previous autosave
+    if course.is_changed() && !course.is_added() {
+        autosave.is_dirty = true;
+    }
+    autosave.snapshot_timer.tick(time.delta());
+    autosave.save_timer.tick(time.delta());
+
+    // the played course is not the one of the editor, which is restored once play mode stops,
+    // and lifted tiles are missing from the course until they are placed again
+    if autosave.is_dirty
+        && autosave.snapshot_timer.finished()
+        && play_session.is_none()
+        && !tiles_lifted.0
+    {
+        let mut course: Course = course.as_ref().into();
+        course.metadata.modified_at = now_millis();
+        match course.serialize() {
+            Ok(data) => {
+                *SNAPSHOT.lock().unwrap() = Some(encode_hex(&data));
+                autosave.is_saved = false;
+            }
+            Err(err) => error!("Course could not be autosaved: {err}"),
+        }
+        autosave.is_dirty = false;
+        autosave.snapshot_timer.reset();
+    }
+    if !autosave.is_saved && autosave.save_timer.finished() {
+        save_autosave();
+        autosave.is_saved = true;
+        autosave.save_timer.reset();
+    }
+}
+
+/// Writes the latest snapshot of the course to the browser storage.
+///
+/// This is also called from the panic hook, so it must not panic itself.
+pub fn save_autosave() {
+    let snapshot = if let Ok(snapshot) = SNAPSHOT.try_lock() {
+        snapshot
+    } else {
+        return;
+    };
+    if let (Some(snapshot), Some(storage)) = (snapshot.as_ref(), local_storage()) {
+        if storage.set_item(AUTOSAVE_KEY, snapshot).is_err() {
+            error!("Course could not be written to the browser storage");
+        }
+    }
+}
+
+/// Returns the serialized course of the latest autosave, if there is one.
+pub fn load_autosave() -> Option<Vec<u8>> {
+    let hex = local_storage()?.get_item(AUTOSAVE_KEY).ok()??;
+    decode_hex(&hex)
+}
+
+fn local_storage() -> Option<Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+// the storage only holds strings
+fn encode_hex(data: &[u8]) -> String {
+    data.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
@@ -1,13 +1,15 @@
 pub(crate) mod goal_pole;
+pub(crate) mod loader;
 pub(crate) mod object;
 pub(crate) mod resource;
 pub(crate) mod sprites;
+pub(crate) mod start;
 pub(crate) mod tile;
 pub(crate) mod ui_button;
 
-use crate::{GroundTileUpdateEvent, Tile};
+use crate::Tile;
 use bevy::{prelude::*, reflect::TypeUuid};
-use shrm_core::{Course, GroundVariant, ThemeVariant, TileVariant};
+use shrm_core::{AreaId, AreaLink, Course, CourseArea, CourseMetadata, ThemeVariant};
 use std::{
     collections::HashMap,
     sync::{Arc, RwLock},
@@ -18,73 +20,41 @@ use std::{
 pub struct CourseRes {
     pub texture_atlas_handle: Handle<TextureAtlas>,
     pub texture_atlas_handle_transparent: Handle<TextureAtlas>,
+    /// Tiles of the loaded area.
     pub tiles: HashMap<[i32; 2], Tile>,
+    /// Theme of the loaded area.
     pub theme: ThemeVariant,
+    pub area: AreaId,
+    pub width: i32,
+    pub height: i32,
+    /// Every area except the loaded one.
+    pub unloaded_areas: HashMap<AreaId, CourseArea>,
+    pub links: Vec<AreaLink>,
+    pub start_pos: [i32; 2],
     pub goal_pos_x: i32,
+    pub metadata: CourseMetadata,
 }
 
+/// Unloads the current area and loads the given one instead.
+pub struct SwitchAreaEvent(pub AreaId);
+
+/// Changes the theme of the loaded area.
+pub struct ChangeThemeEvent(pub ThemeVariant);
+
 #[derive(Resource)]
-pub struct CourseLoading(pub Arc<RwLock<Option<Course>>>);
+pub struct CourseLoading(pub Arc<RwLock<Option<Result<Course, String>>>>);
 
 impl From<&CourseRes> for Course {
     fn from(course: &CourseRes) -> Self {
+        let mut areas = course.unloaded_areas.clone();
+        areas.insert(course.area, course.loaded_area());
         Self {
-            tiles: {
-                let mut tiles = HashMap::new();
-                for (pos, tile) in course.tiles.clone() {
-                    tiles.insert(pos, tile.variant);
-                }
-                tiles
-            },
-            theme: course.theme.clone(),
+            main_area: areas.remove(&AreaId::Main).unwrap(),
+            sub_area: areas.remove(&AreaId::Sub).unwrap(),
+            links: course.links.clone(),
+            start_pos: course.start_pos,
             goal_pos_x: course.goal_pos_x,
+            metadata: course.metadata.clone(),
         }
     }
 }
-
-pub fn get_surrounding_matrix(
-    grid_pos: &[i32; 2],
-    tiles: &mut HashMap<[i32; 2], Tile>,
-    events: &mut HashMap<Entity, GroundTileUpdateEvent>,
-) -> [[bool; 3]; 3] {
-    let mut surrounding_matrix = [
-        [false, false, false],
-        [false, false, false],
-        [false, false, false],
-    ];
-    for x in grid_pos[0] - 1..=grid_pos[0] + 1 {
-        for y in grid_pos[1] - 1..=grid_pos[1] + 1 {
-            let pos = [x, y];
-            if &pos == grid_pos {
-                continue;
-            }
-            if x < 0 || y < 0 {
-                surrounding_matrix[(grid_pos[1] - y + 1) as usize]
-                    [(x - grid_pos[0] + 1) as usize] = true;
-                continue;
-            }
-
-            if let Some(Tile {
-                entity,
-                variant: TileVariant::Ground(ground_variant),
-                mtrx,
-            }) = tiles.get_mut(&pos)
-            {
-                surrounding_matrix[(grid_pos[1] - y + 1) as usize]
-                    [(x - grid_pos[0] + 1) as usize] = true;
-                if let Some(mtrx) = mtrx {
-                    mtrx.0[(y - grid_pos[1] + 1) as usize][(grid_pos[0] - x + 1) as usize] = true;
-                    *ground_variant = GroundVariant::from_surrounding_matrix(&mtrx.0);
-                    events.insert(
-                        *entity,
-                        GroundTileUpdateEvent {
-                            entity: *entity,
-                            index: ground_variant.get_sprite_sheet_index(),
-                        },
-                    );
-                }
-            }
-        }
-    }
-    surrounding_matrix
-}
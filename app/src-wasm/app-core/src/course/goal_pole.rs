@@ -2,7 +2,7 @@ use std::{collections::HashMap, time::Duration};
 
 use crate::{
     grid_to_world, grid_to_world_f32, pos_to_world, CourseRes, DespawnTileEvent, DragEventFlags,
-    Draggable, GroundTileUpdateEvent, ObjectSpriteHandles, ObjectVariant,
+    Draggable, EditCommand, EditHistory, GroundTileUpdateEvent, ObjectSpriteHandles, ObjectVariant,
 };
 use app_config::*;
 use bevy::prelude::*;
@@ -12,6 +12,15 @@ use shrm_core::{GroundVariant, TileVariant};
 #[derive(Component)]
 pub struct GoalPole(i32);
 
+/// Touching it clears the course.
+#[derive(Component)]
+pub struct GoalSensor;
+
+pub struct CourseClearedEvent {
+    /// Time from the last spawn at the start until the goal was touched.
+    pub clear_time: Duration,
+}
+
 pub struct GoalPoleDragEvent {
     pub direction: GoalPoleDragDirection,
 }
@@ -120,6 +129,16 @@ impl CourseRes {
             },
             GoalPole(self.goal_pos_x + 1),
         ));
+
+        // covers the pole from the ground up to the top of the flag
+        let world_pos = grid_to_world_f32(&[self.goal_pos_x as f32 + 1., 6.]);
+        commands.spawn((
+            TransformBundle::from_transform(Transform::from_xyz(world_pos.x, world_pos.y, 0.)),
+            Collider::cuboid(pos_to_world(1) / 4., pos_to_world(4)),
+            Sensor,
+            GoalSensor,
+            GoalPole(self.goal_pos_x + 1),
+        ));
     }
 
     pub(crate) fn spawn_goal_drag(&mut self, commands: &mut Commands, asset_server: &AssetServer) {
@@ -189,6 +208,7 @@ impl CourseRes {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn move_goal_pole(
     query: Query<Entity, (With<GoalPole>, Without<Draggable>)>,
     mut drag_query: Query<&mut Transform, (With<GoalPole>, With<Draggable>)>,
@@ -197,13 +217,37 @@ pub fn move_goal_pole(
     despawn_tile_events: EventWriter<DespawnTileEvent>,
     mut respawn_events: EventWriter<RespawnGoalPoleEvent>,
     mut course: ResMut<CourseRes>,
+    mut history: ResMut<EditHistory>,
 ) {
     if let Some(GoalPoleDragEvent { direction }) = drag_events.iter().next() {
+        let from = course.goal_pos_x;
+        if let GoalPoleDragDirection::Left = direction {
+            // the goal ground replaces the tiles left of it
+            for y in 0..FIXED_GROUND_HEIGHT {
+                let grid_pos = [from - 1, y];
+                if let Some(tile) = course.tiles.get(&grid_pos) {
+                    history.record(
+                        course.area,
+                        EditCommand::RemoveTile {
+                            grid_pos,
+                            variant: tile.variant.clone(),
+                        },
+                    );
+                }
+            }
+        }
         course.despawn_goal(query, direction, commands, despawn_tile_events);
         course.goal_pos_x += match direction {
             GoalPoleDragDirection::Left => -1,
             GoalPoleDragDirection::Right => 1,
         };
+        history.record(
+            course.area,
+            EditCommand::MoveGoal {
+                from,
+                to: course.goal_pos_x,
+            },
+        );
         if let Ok(mut transform) = drag_query.get_single_mut() {
             let world_pos = pos_to_world(course.goal_pos_x + 1);
             transform.translation.x = world_pos;
@@ -0,0 +1,68 @@
+use crate::{
+    CourseRes, EditHistory, GoalPole, GroundTileUpdateEvent, ObjectSpriteHandles, StartMarker,
+};
+use bevy::{ecs::system::SystemParam, prelude::*};
+use shrm_core::{AreaId, Course, CourseArea};
+
+/// Loads courses and areas in place of the ones already spawned.
+#[allow(clippy::type_complexity)]
+#[derive(SystemParam)]
+pub struct CourseLoader<'w, 's> {
+    pub(crate) commands: Commands<'w, 's>,
+    /// Entities that only exist while the main area is loaded, besides its tiles.
+    main_area_query: Query<'w, 's, Entity, Or<(With<GoalPole>, With<StartMarker>)>>,
+    asset_server: Res<'w, AssetServer>,
+    texture_atlases: ResMut<'w, Assets<TextureAtlas>>,
+    pub(crate) object_sprite_handles: Res<'w, ObjectSpriteHandles>,
+    pub(crate) ground_tile_update_events: EventWriter<'w, 's, GroundTileUpdateEvent>,
+    pub(crate) history: ResMut<'w, EditHistory>,
+}
+
+impl<'w, 's> CourseLoader<'w, 's> {
+    /// Despawns everything of `course_res` and loads `course` instead.
+    ///
+    /// The edit history is cleared, since its entries belong to the previous course.
+    pub fn reload(&mut self, course_res: &mut CourseRes, course: &Course) {
+        self.unload(course_res);
+        self.history.clear();
+        *course_res = CourseRes::load(
+            &mut self.commands,
+            course,
+            &self.asset_server,
+            &mut self.texture_atlases,
+            &self.object_sprite_handles,
+            &mut self.ground_tile_update_events,
+        );
+    }
+
+    pub fn switch_area(&mut self, course_res: &mut CourseRes, area: AreaId) {
+        if area == course_res.area {
+            return;
+        }
+        let next_area = if let Some(next_area) = course_res.unloaded_areas.remove(&area) {
+            next_area
+        } else {
+            return;
+        };
+
+        let prev_area = course_res.area;
+        let unloaded_area = self.unload(course_res);
+        course_res.unloaded_areas.insert(prev_area, unloaded_area);
+        course_res.load_area(
+            &mut self.commands,
+            area,
+            &next_area,
+            &self.asset_server,
+            &mut self.texture_atlases,
+            &self.object_sprite_handles,
+            &mut self.ground_tile_update_events,
+        );
+    }
+
+    fn unload(&mut self, course_res: &mut CourseRes) -> CourseArea {
+        for entity in self.main_area_query.iter() {
+            self.commands.entity(entity).despawn_recursive();
+        }
+        course_res.unload_area(&mut self.commands)
+    }
+}
@@ -1,11 +1,14 @@
 use crate::{
-    get_surrounding_matrix, grid_to_world, CourseRes, Ground, GroundSurroundingMatrix,
-    GroundTileUpdateEvent, ObjectSpriteHandles, Tile, TileNotEditable,
+    grid_to_world, now_millis, CourseRes, Ground, GroundTileUpdateEvent, ObjectSpriteHandles, Tile,
+    TileCollider, TileNotEditable, TileSprite,
 };
 use app_config::*;
 use bevy::prelude::*;
 use bevy_rapier::prelude::*;
-use shrm_core::{Course, GroundVariant, ThemeVariant, TileVariant};
+use shrm_core::{
+    retile_around, AreaId, Course, CourseArea, CourseMetadata, GroundVariant, ThemeVariant,
+    TileVariant,
+};
 use std::collections::HashMap;
 
 impl CourseRes {
@@ -17,51 +20,36 @@ impl CourseRes {
         object_sprite_handles: &ObjectSpriteHandles,
         ground_tile_update_events: &mut EventWriter<GroundTileUpdateEvent>,
     ) -> Self {
-        let (texture_atlas_handle, texture_atlas_handle_transparent) =
-            Self::load_handles(&theme, asset_server, texture_atlases);
-        let mut course = CourseRes {
-            texture_atlas_handle,
-            texture_atlas_handle_transparent,
-            tiles: HashMap::default(),
-            theme,
-            goal_pos_x: 32,
+        let mut main_area = CourseArea::new(theme);
+        // the start area at the default start position
+        for x in 0..START_AREA_WIDTH {
+            for y in 0..FIXED_GROUND_HEIGHT {
+                main_area
+                    .tiles
+                    .insert([x, y], TileVariant::Ground(GroundVariant::default()));
+            }
+        }
+        let course = Course {
+            main_area,
+            sub_area: CourseArea::new(ThemeVariant::Underground),
+            links: vec![],
+            start_pos: [0, 0],
+            goal_pos_x: MIN_GOAL_POS_X,
+            metadata: CourseMetadata {
+                created_at: now_millis(),
+                modified_at: now_millis(),
+                ..Default::default()
+            },
         };
 
-        let mut events = HashMap::new();
-        for x in 0..7 {
-            course.spawn_tile(
-                commands,
-                &[x, 0],
-                &TileVariant::Ground(GroundVariant::Full0),
-                &mut events,
-            );
-            course.spawn_tile(
-                commands,
-                &[x, 1],
-                &TileVariant::Ground(GroundVariant::Top0),
-                &mut events,
-            );
-        }
-        course.spawn_tile(
-            commands,
-            &[7, 0],
-            &TileVariant::Ground(GroundVariant::Right0),
-            &mut events,
-        );
-        course.spawn_tile(
+        Self::load(
             commands,
-            &[7, 1],
-            &TileVariant::Ground(GroundVariant::TopRight0),
-            &mut events,
-        );
-        for event in events.into_values() {
-            ground_tile_update_events.send(event);
-        }
-
-        course.spawn_goal(commands, object_sprite_handles, ground_tile_update_events);
-        course.spawn_goal_drag(commands, asset_server);
-
-        course
+            &course,
+            asset_server,
+            texture_atlases,
+            object_sprite_handles,
+            ground_tile_update_events,
+        )
     }
 
     pub fn load(
@@ -72,25 +60,161 @@ impl CourseRes {
         object_sprite_handles: &ObjectSpriteHandles,
         ground_tile_update_events: &mut EventWriter<GroundTileUpdateEvent>,
     ) -> Self {
-        let (texture_atlas_handle, texture_atlas_handle_transparent) =
-            Self::load_handles(&course.theme, asset_server, texture_atlases);
         let mut course_res = CourseRes {
-            texture_atlas_handle,
-            texture_atlas_handle_transparent,
+            texture_atlas_handle: Handle::default(),
+            texture_atlas_handle_transparent: Handle::default(),
             tiles: HashMap::default(),
-            theme: course.theme.clone(),
+            theme: course.main_area.theme.clone(),
+            area: AreaId::Main,
+            width: course.main_area.width,
+            height: course.main_area.height,
+            unloaded_areas: HashMap::from([(AreaId::Sub, course.sub_area.clone())]),
+            links: course.links.clone(),
+            start_pos: course.start_pos,
             goal_pos_x: course.goal_pos_x,
+            metadata: course.metadata.clone(),
         };
+        course_res.load_area(
+            commands,
+            AreaId::Main,
+            &course.main_area,
+            asset_server,
+            texture_atlases,
+            object_sprite_handles,
+            ground_tile_update_events,
+        );
+
+        course_res
+    }
+
+    /// Spawns the tiles of `course_area`, which must not be loaded yet.
+    ///
+    /// The previously loaded area has to be unloaded with [`CourseRes::unload_area`] first.
+    #[allow(clippy::too_many_arguments)]
+    pub fn load_area(
+        &mut self,
+        commands: &mut Commands,
+        area: AreaId,
+        course_area: &CourseArea,
+        asset_server: &AssetServer,
+        texture_atlases: &mut Assets<TextureAtlas>,
+        object_sprite_handles: &ObjectSpriteHandles,
+        ground_tile_update_events: &mut EventWriter<GroundTileUpdateEvent>,
+    ) {
+        let (texture_atlas_handle, texture_atlas_handle_transparent) =
+            Self::load_handles(&course_area.theme, asset_server, texture_atlases);
+        self.texture_atlas_handle = texture_atlas_handle;
+        self.texture_atlas_handle_transparent = texture_atlas_handle_transparent;
+        self.area = area;
+        self.theme = course_area.theme.clone();
+        self.width = course_area.width;
+        self.height = course_area.height;
 
         let mut events = HashMap::new();
-        for (grid_pos, tile) in course.tiles.clone() {
-            course_res.spawn_tile(commands, &grid_pos, &tile, &mut events);
+        for (grid_pos, tile) in course_area.tiles.iter() {
+            self.spawn_tile(commands, grid_pos, tile, &mut events);
+        }
+        for event in events.into_values() {
+            ground_tile_update_events.send(event);
         }
 
-        course_res.spawn_goal(commands, object_sprite_handles, ground_tile_update_events);
-        course_res.spawn_goal_drag(commands, asset_server);
+        if area == AreaId::Main {
+            self.spawn_start_marker(commands);
+            self.spawn_goal(commands, object_sprite_handles, ground_tile_update_events);
+            self.spawn_goal_drag(commands, asset_server);
+        }
+    }
 
-        course_res
+    /// Despawns all tiles of the loaded area and returns its data.
+    ///
+    /// Entities of the start marker and the goal have to be despawned separately,
+    /// see [`CourseLoader`](crate::CourseLoader).
+    pub fn unload_area(&mut self, commands: &mut Commands) -> CourseArea {
+        let course_area = self.loaded_area();
+        for (_, tile) in self.tiles.drain() {
+            commands.entity(tile.entity).despawn_recursive();
+        }
+        course_area
+    }
+
+    /// Resizes the loaded area and despawns all tiles outside of the new bounds.
+    pub fn resize(
+        &mut self,
+        commands: &mut Commands,
+        width: i32,
+        height: i32,
+        events: &mut HashMap<Entity, GroundTileUpdateEvent>,
+    ) {
+        self.width = width;
+        self.height = height;
+        let outside: Vec<_> = self
+            .tiles
+            .keys()
+            .filter(|[x, y]| *x >= width || *y >= height)
+            .copied()
+            .collect();
+        for grid_pos in outside {
+            if let Some(tile) = self.tiles.remove(&grid_pos) {
+                commands.entity(tile.entity).despawn_recursive();
+                self.retile_around(&grid_pos, events);
+            }
+        }
+    }
+
+    pub fn loaded_area(&self) -> CourseArea {
+        CourseArea {
+            tiles: self
+                .tiles
+                .iter()
+                .map(|(pos, tile)| (*pos, tile.variant.clone()))
+                .collect(),
+            theme: self.theme.clone(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    /// Changes the theme of the loaded area and swaps the texture atlas of its tiles.
+    pub fn set_theme(
+        &mut self,
+        theme: ThemeVariant,
+        asset_server: &AssetServer,
+        texture_atlases: &mut Assets<TextureAtlas>,
+        child_query: &Query<&Children>,
+        atlas_query: &mut Query<&mut Handle<TextureAtlas>, With<TileSprite>>,
+    ) {
+        let (texture_atlas_handle, texture_atlas_handle_transparent) =
+            Self::load_handles(&theme, asset_server, texture_atlases);
+        for tile in self.tiles.values() {
+            if let Ok(children) = child_query.get(tile.entity) {
+                for child in children.iter() {
+                    if let Ok(mut handle) = atlas_query.get_mut(*child) {
+                        *handle = texture_atlas_handle.clone();
+                    }
+                }
+            }
+        }
+        self.texture_atlas_handle = texture_atlas_handle;
+        self.texture_atlas_handle_transparent = texture_atlas_handle_transparent;
+        self.theme = theme;
+    }
+
+    /// Exclusive bound of the loaded area on the x axis.
+    ///
+    /// The main area ends shortly after the goal.
+    pub fn right_edge(&self) -> i32 {
+        if self.area == AreaId::Main {
+            self.goal_pos_x + MAX_COURSE_GOAL_OFFSET_X
+        } else {
+            self.width
+        }
+    }
+
+    /// Whether a tile can be placed at `grid_pos`.
+    pub fn is_free(&self, grid_pos: &[i32; 2]) -> bool {
+        (0..self.right_edge()).contains(&grid_pos[0])
+            && (0..self.height).contains(&grid_pos[1])
+            && !self.tiles.contains_key(grid_pos)
     }
 
     fn load_handles(
@@ -123,38 +247,24 @@ impl CourseRes {
         tile_variant: &TileVariant,
         events: &mut HashMap<Entity, GroundTileUpdateEvent>,
     ) {
-        if self.tiles.contains_key(grid_pos) {
+        if !self.is_free(grid_pos) {
             return;
         }
 
         let world_pos = grid_to_world(grid_pos);
-        if grid_pos[0] < 0
-            || grid_pos[1] < 0
-            || grid_pos[0] > self.goal_pos_x + MAX_COURSE_GOAL_OFFSET_X
-            || grid_pos[1] > MAX_COURSE_Y
-        {
-            return;
-        }
 
-        let mut tile_variant = tile_variant.clone();
-        let (surrounding_matrix, ground_variant) =
-            if let TileVariant::Ground(ground_variant) = &mut tile_variant {
-                let surrounding_matrix = get_surrounding_matrix(grid_pos, &mut self.tiles, events);
-                *ground_variant = GroundVariant::from_surrounding_matrix(&surrounding_matrix);
-                (
-                    Some(GroundSurroundingMatrix(surrounding_matrix)),
-                    Some(ground_variant.clone()),
-                )
-            } else {
-                (None, None)
-            };
-
-        let sprite = if let Some(ground_variant) = ground_variant {
-            TextureAtlasSprite::new(ground_variant.get_sprite_sheet_index())
-        } else {
-            TextureAtlasSprite::new(tile_variant.get_sprite_sheet_index())
-        };
-        let mut entity_commands = commands.spawn_empty();
+        let entity = commands.spawn_empty().id();
+        self.tiles.insert(
+            *grid_pos,
+            Tile {
+                entity,
+                variant: tile_variant.clone(),
+            },
+        );
+        self.retile_around(grid_pos, events);
+
+        let sprite = TextureAtlasSprite::new(self.tiles[grid_pos].variant.get_sprite_sheet_index());
+        let mut entity_commands = commands.entity(entity);
         entity_commands
             .insert((
                 RigidBody::Fixed,
@@ -187,36 +297,47 @@ impl CourseRes {
                         ],
                         None,
                     ),
-                    Friction::new(GROUND_FRICTION),
                     Ground,
                 ));
-                parent.spawn(SpriteSheetBundle {
-                    transform: Transform {
-                        scale: Vec3::new(TILE_SIZE, TILE_SIZE, 0.),
+                parent.spawn((
+                    SpriteSheetBundle {
+                        transform: Transform {
+                            scale: Vec3::new(TILE_SIZE, TILE_SIZE, 0.),
+                            ..Default::default()
+                        },
+                        texture_atlas: self.texture_atlas_handle.clone(),
+                        sprite,
                         ..Default::default()
                     },
-                    texture_atlas: self.texture_atlas_handle.clone(),
-                    sprite,
-                    ..Default::default()
-                });
+                    TileSprite,
+                ));
                 parent.spawn((
                     Collider::cuboid(TILE_GRID_SIZE * TILE_SIZE, TILE_GRID_SIZE * TILE_SIZE),
                     Friction::new(0.),
+                    TileCollider,
                 ));
             });
-        let is_goal = grid_pos[0] >= self.goal_pos_x && grid_pos[1] <= 1;
-        let is_start = grid_pos[0] < 8 && grid_pos[1] <= 1;
-        if is_goal || is_start {
+        let is_goal = grid_pos[0] >= self.goal_pos_x && grid_pos[1] < FIXED_GROUND_HEIGHT;
+        if self.area == AreaId::Main && (is_goal || self.is_start_area(grid_pos)) {
             entity_commands.insert(TileNotEditable);
         }
+    }
 
-        let entity = entity_commands.id();
-
-        let tile = Tile {
-            entity,
-            variant: tile_variant,
-            mtrx: surrounding_matrix,
-        };
-        self.tiles.insert(*grid_pos, tile);
+    /// Autotiles the ground at and around `grid_pos` and queues sprite updates for changed tiles.
+    pub fn retile_around(
+        &mut self,
+        grid_pos: &[i32; 2],
+        events: &mut HashMap<Entity, GroundTileUpdateEvent>,
+    ) {
+        for pos in retile_around(&mut self.tiles, grid_pos) {
+            let Tile { entity, variant } = &self.tiles[&pos];
+            events.insert(
+                *entity,
+                GroundTileUpdateEvent {
+                    entity: *entity,
+                    index: variant.get_sprite_sheet_index(),
+                },
+            );
+        }
     }
 }
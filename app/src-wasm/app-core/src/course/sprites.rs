@@ -8,6 +8,13 @@ use super::object::ObjectVariant;
 #[derive(Default, Resource)]
 pub struct TileSpriteHandles(pub HashMap<TileVariant, Handle<Image>>);
 
+/// Icons of the palette tiles for the themes other than plain.
+///
+/// Unlike the plain icons, they are not awaited on setup,
+/// so that a missing file only falls back to the plain icon.
+#[derive(Default, Resource)]
+pub struct ThemedTileSpriteHandles(pub HashMap<(ThemeVariant, TileVariant), Handle<Image>>);
+
 #[derive(Default, Resource)]
 pub struct TileSpriteHandlesTransparent(pub HashMap<TileVariant, Handle<Image>>);
 
@@ -22,6 +29,7 @@ pub struct ObjectSpriteHandles(pub HashMap<ObjectVariant, Handle<Image>>);
 
 pub(crate) fn load_course_sprites(
     mut tile_sprite_handles: ResMut<TileSpriteHandles>,
+    mut themed_tile_sprite_handles: ResMut<ThemedTileSpriteHandles>,
     mut tile_sprite_handles_transparent: ResMut<TileSpriteHandlesTransparent>,
     mut theme_sprite_handles: ResMut<ThemeSpriteHandles>,
     mut ui_button_sprite_handles: ResMut<UiButtonSpriteHandles>,
@@ -37,6 +45,18 @@ pub(crate) fn load_course_sprites(
         );
     }
 
+    themed_tile_sprite_handles.0 = HashMap::default();
+    for theme_variant in all::<ThemeVariant>().filter(|theme| *theme != ThemeVariant::Plain) {
+        let name = theme_variant.get_name().to_string();
+        for tile_variant in TileVariant::palette() {
+            let index = tile_variant.get_sprite_sheet_index();
+            themed_tile_sprite_handles.0.insert(
+                (theme_variant.clone(), tile_variant),
+                asset_server.load(format!("MW_Field_{name}_0_{index}.png")),
+            );
+        }
+    }
+
     tile_sprite_handles_transparent.0 = HashMap::default();
     for tile_variant in all::<TileVariant>().collect::<Vec<_>>().into_iter() {
         let index = tile_variant.get_sprite_sheet_index();
@@ -0,0 +1,171 @@
+use crate::{
+    grid_to_world, CourseRes, DespawnTileEvent, DragEventFlags, Draggable, EditCommand,
+    EditHistory, GroundTileUpdateEvent,
+};
+use app_config::*;
+use bevy::prelude::*;
+use bevy_rapier::prelude::*;
+use shrm_core::{GroundVariant, TileVariant, PLAYER_START_OFFSET};
+use std::collections::HashMap;
+
+/// Position of the start marker relative to the start position.
+const START_MARKER_OFFSET: [i32; 2] = [1, 1];
+
+#[derive(Component)]
+pub struct StartMarker;
+
+pub struct StartMarkerDragEvent {
+    pub grid_pos: [i32; 2],
+}
+
+pub struct RespawnStartEvent;
+
+impl CourseRes {
+    /// Grid position the player spawns at.
+    pub fn player_start(&self) -> [i32; 2] {
+        [
+            self.start_pos[0] + PLAYER_START_OFFSET[0],
+            self.start_pos[1] + PLAYER_START_OFFSET[1],
+        ]
+    }
+
+    pub fn is_start_area(&self, grid_pos: &[i32; 2]) -> bool {
+        (self.start_pos[0]..self.start_pos[0] + START_AREA_WIDTH).contains(&grid_pos[0])
+            && (self.start_pos[1]..self.start_pos[1] + FIXED_GROUND_HEIGHT).contains(&grid_pos[1])
+    }
+
+    pub(crate) fn start_area(&self) -> impl Iterator<Item = [i32; 2]> {
+        let [start_x, start_y] = self.start_pos;
+        (start_y..start_y + FIXED_GROUND_HEIGHT)
+            .flat_map(move |y| (start_x..start_x + START_AREA_WIDTH).map(move |x| [x, y]))
+    }
+
+    pub(crate) fn spawn_start(
+        &mut self,
+        commands: &mut Commands,
+        ground_tile_update_events: &mut EventWriter<GroundTileUpdateEvent>,
+    ) {
+        let mut events = HashMap::new();
+        for grid_pos in self.start_area().collect::<Vec<_>>() {
+            self.spawn_tile(
+                commands,
+                &grid_pos,
+                &TileVariant::Ground(GroundVariant::default()),
+                &mut events,
+            );
+        }
+        for event in events.into_values() {
+            ground_tile_update_events.send(event);
+        }
+    }
+
+    pub(crate) fn spawn_start_marker(&self, commands: &mut Commands) {
+        let world_pos = grid_to_world(&start_marker_pos(self.start_pos));
+        commands
+            .spawn((
+                RigidBody::Fixed,
+                SpatialBundle {
+                    transform: Transform::from_xyz(world_pos.x, world_pos.y, Z_INDEX_START_MARKER),
+                    visibility: Visibility { is_visible: true },
+                    ..default()
+                },
+            ))
+            .with_children(|parent| {
+                parent.spawn(SpriteBundle {
+                    sprite: Sprite {
+                        color: START_MARKER_COLOR,
+                        custom_size: Some(Vec2::splat(GRID_SIZE * RAPIER_SCALE * 0.8)),
+                        ..default()
+                    },
+                    ..default()
+                });
+            })
+            .insert(Collider::cuboid(
+                TILE_GRID_SIZE * TILE_SIZE,
+                TILE_GRID_SIZE * TILE_SIZE,
+            ))
+            .insert(Sensor)
+            .insert(Draggable {
+                flags: DragEventFlags::empty(),
+            })
+            .insert(StartMarker);
+    }
+}
+
+pub(crate) fn start_marker_pos(start_pos: [i32; 2]) -> [i32; 2] {
+    [
+        start_pos[0] + START_MARKER_OFFSET[0],
+        start_pos[1] + START_MARKER_OFFSET[1],
+    ]
+}
+
+/// Moves the start area with the start marker.
+///
+/// Tiles in the way of the new start area get replaced by its ground.
+pub fn move_start_marker(
+    mut marker_query: Query<&mut Transform, With<StartMarker>>,
+    mut drag_events: EventReader<StartMarkerDragEvent>,
+    mut despawn_tile_events: EventWriter<DespawnTileEvent>,
+    mut respawn_events: EventWriter<RespawnStartEvent>,
+    mut course: ResMut<CourseRes>,
+    mut history: ResMut<EditHistory>,
+) {
+    if let Some(StartMarkerDragEvent { grid_pos }) = drag_events.iter().last() {
+        let start_pos = [
+            (grid_pos[0] - START_MARKER_OFFSET[0]).clamp(0, course.goal_pos_x - START_AREA_WIDTH),
+            (grid_pos[1] - START_MARKER_OFFSET[1])
+                .clamp(0, course.height - PLAYER_START_OFFSET[1] - 1),
+        ];
+        if start_pos == course.start_pos {
+            return;
+        }
+
+        let from = course.start_pos;
+        let prev_area: Vec<_> = course.start_area().collect();
+        despawn_tile_events.send_batch(prev_area.iter().map(|grid_pos| DespawnTileEvent {
+            grid_pos: *grid_pos,
+            force: true,
+        }));
+        course.start_pos = start_pos;
+        for grid_pos in course.start_area().filter(|pos| !prev_area.contains(pos)) {
+            if let Some(tile) = course.tiles.get(&grid_pos) {
+                history.record(
+                    course.area,
+                    EditCommand::RemoveTile {
+                        grid_pos,
+                        variant: tile.variant.clone(),
+                    },
+                );
+            }
+        }
+        history.record(
+            course.area,
+            EditCommand::MoveStart {
+                from,
+                to: start_pos,
+            },
+        );
+        despawn_tile_events.send_batch(course.start_area().map(|grid_pos| DespawnTileEvent {
+            grid_pos,
+            force: true,
+        }));
+
+        if let Ok(mut transform) = marker_query.get_single_mut() {
+            let world_pos = grid_to_world(&start_marker_pos(start_pos));
+            transform.translation.x = world_pos.x;
+            transform.translation.y = world_pos.y;
+        }
+        respawn_events.send(RespawnStartEvent);
+    }
+}
+
+pub fn respawn_start(
+    mut commands: Commands,
+    mut course: ResMut<CourseRes>,
+    mut respawn_events: EventReader<RespawnStartEvent>,
+    mut ground_tile_update_events: EventWriter<GroundTileUpdateEvent>,
+) {
+    if respawn_events.iter().next().is_some() {
+        course.spawn_start(&mut commands, &mut ground_tile_update_events);
+    }
+}
@@ -1,5 +1,6 @@
+use crate::CourseRes;
 use bevy::prelude::*;
-use shrm_core::TileVariant;
+use shrm_core::{flood_fill, line, rect, MapTile, TileVariant};
 
 #[derive(Component)]
 #[repr(transparent)]
@@ -11,6 +12,15 @@ pub struct TilePreview;
 #[derive(Component)]
 pub struct TileNotEditable;
 
+/// The sprite among the children of a tile.
+#[derive(Component)]
+pub struct TileSprite;
+
+/// The solid collider among the children of a tile, unlike the [`Ground`](crate::Ground) on top.
+#[derive(Component)]
+pub struct TileCollider;
+
+/// Parent of the preview sprites, together with the grid position of the cursor.
 #[derive(Resource)]
 pub struct TilePlacePreview(pub Option<(Entity, [i32; 2])>);
 
@@ -18,11 +28,72 @@ pub struct TilePlacePreview(pub Option<(Entity, [i32; 2])>);
 pub struct Tile {
     pub entity: Entity,
     pub variant: TileVariant,
-    pub mtrx: Option<GroundSurroundingMatrix>,
 }
 
-#[derive(Clone, Debug)]
-pub struct GroundSurroundingMatrix(pub [[bool; 3]; 3]);
+impl MapTile for Tile {
+    fn variant(&self) -> &TileVariant {
+        &self.variant
+    }
+
+    fn variant_mut(&mut self) -> &mut TileVariant {
+        &mut self.variant
+    }
+}
 
 #[derive(Debug, Default, Resource)]
 pub struct SelectedTile(pub Option<TileVariant>);
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Resource)]
+pub enum EditorTool {
+    #[default]
+    Pencil,
+    Line,
+    Rect,
+    HollowRect,
+    Fill,
+    Select,
+}
+
+/// Grid position where the current line or rectangle was started.
+#[derive(Debug, Default, Resource)]
+pub struct ToolStart(pub Option<[i32; 2]>);
+
+/// Whether the selection holds tiles that were lifted off the loaded area and not placed yet.
+#[derive(Debug, Default, Resource)]
+pub struct TilesLifted(pub bool);
+
+impl EditorTool {
+    pub fn is_shape(&self) -> bool {
+        matches!(self, Self::Line | Self::Rect | Self::HollowRect)
+    }
+
+    /// Empty cells of the loaded area the tool places tiles at.
+    ///
+    /// For the pencil, `start` is the cursor position of the previous frame,
+    /// so that fast strokes do not leave gaps.
+    pub fn cells(
+        &self,
+        course: &CourseRes,
+        start: Option<[i32; 2]>,
+        cursor: [i32; 2],
+    ) -> Vec<[i32; 2]> {
+        let cells = match (self, start) {
+            (Self::Pencil | Self::Line, Some(start)) => line(start, cursor),
+            (Self::Rect, Some(start)) => rect(start, cursor, true),
+            (Self::HollowRect, Some(start)) => rect(start, cursor, false),
+            (Self::Select, _) => return vec![],
+            (Self::Fill, _) => {
+                return flood_fill(&course.tiles, cursor, course.right_edge(), course.height)
+            }
+            _ => vec![cursor],
+        };
+        cells
+            .into_iter()
+            .filter(|grid_pos @ [x, y]| {
+                (0..course.right_edge()).contains(x)
+                    && (0..course.height).contains(y)
+                    && !course.tiles.contains_key(grid_pos)
+            })
+            .collect()
+    }
+}
@@ -1,8 +1,9 @@
 use crate::{
-    cursor_to_world, grid_to_world, world_to_grid, world_to_grid_pos, GoalPole,
-    GoalPoleDragDirection, GoalPoleDragEvent, GoalPoleDragTimer, MainCameraQuery,
+    cursor_to_world, grid_to_world, world_to_grid, world_to_grid_pos, CourseRes, GameMode,
+    GoalPole, GoalPoleDragDirection, GoalPoleDragEvent, GoalPoleDragTimer, MainCameraQuery,
+    StartMarker, StartMarkerDragEvent,
 };
-use app_config::{MAX_GOAL_POS_X, MIN_GOAL_POS_X};
+use app_config::{MAX_COURSE_GOAL_OFFSET_X, MIN_GOAL_POS_X, START_AREA_WIDTH};
 use bevy::{input::mouse::MouseMotion, prelude::*};
 use bevy_rapier::prelude::*;
 
@@ -79,7 +80,11 @@ pub fn drag_mouse_button(
     mut dragging: ResMut<Dragging>,
     mouse: Res<Input<MouseButton>>,
     windows: Res<Windows>,
+    game_mode: Res<GameMode>,
 ) {
+    if let GameMode::Play = *game_mode {
+        return;
+    }
     let window = windows.get_primary().unwrap();
     let cursor_position = if let Some(cursor_pointer) = window.cursor_position() {
         cursor_pointer
@@ -110,11 +115,15 @@ pub fn drag_mouse_button(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn handle_drag_events(
     mut query: Query<&mut Transform>,
     mut goal_pole_query: Query<&mut GoalPoleDragTimer, With<GoalPole>>,
+    start_marker_query: Query<(), With<StartMarker>>,
     mut drag_events: EventReader<DragEvent>,
     mut goal_pole_drag_events: EventWriter<GoalPoleDragEvent>,
+    mut start_marker_drag_events: EventWriter<StartMarkerDragEvent>,
+    course: Res<CourseRes>,
     time: Res<Time>,
 ) {
     for DragEvent { entity, grid_pos } in drag_events.iter() {
@@ -137,11 +146,25 @@ pub fn handle_drag_events(
                 };
                 match direction {
                     GoalPoleDragDirection::Left if old_grid_pos - 1 < MIN_GOAL_POS_X => return,
-                    GoalPoleDragDirection::Right if old_grid_pos + 1 > MAX_GOAL_POS_X => return,
+                    // the goal must not overlap the start area
+                    GoalPoleDragDirection::Left
+                        if old_grid_pos - 2 < course.start_pos[0] + START_AREA_WIDTH =>
+                    {
+                        return
+                    }
+                    GoalPoleDragDirection::Right
+                        if old_grid_pos + 1 > course.width - MAX_COURSE_GOAL_OFFSET_X =>
+                    {
+                        return
+                    }
                     _ => {}
                 };
                 timer.reset();
                 goal_pole_drag_events.send(GoalPoleDragEvent { direction });
+            } else if start_marker_query.get(*entity).is_ok() {
+                start_marker_drag_events.send(StartMarkerDragEvent {
+                    grid_pos: *grid_pos,
+                });
             } else {
                 transform.translation = world_pos.extend(0.);
             }
@@ -1,4 +1,6 @@
 use bevy::prelude::*;
+use shrm_core::{Course, Replay};
+use std::time::Duration;
 
 #[derive(Debug, Resource)]
 pub enum GameMode {
@@ -13,6 +15,44 @@ pub struct GameModeToggleEvent {
 #[derive(Component)]
 pub struct GameModeEdit;
 
+/// UI that is hidden in play mode.
+#[derive(Component)]
+pub struct GameModeBuild;
+
+pub enum PlayModeEvent {
+    Start,
+    /// Undoes all play-time changes and drops back into editing.
+    Stop,
+    /// Rebuilds the course and starts over with all lives.
+    Restart,
+    /// Loads the course of the replay and plays it with the recorded input.
+    Replay(Box<Replay>),
+}
+
+/// Present while in [`GameMode::Play`].
+#[derive(Resource)]
+pub struct PlaySession {
+    /// The course that is played, which restarts and respawns rebuild.
+    pub course: Course,
+    /// The course of the editor, which is restored once play mode stops.
+    ///
+    /// Differs from `course` when a replay plays back the course it was recorded on.
+    pub editor_course: Course,
+    pub lives: u8,
+    pub state: PlayState,
+}
+
+pub enum PlayState {
+    Playing,
+    Dying { timer: Timer, start_y: f32 },
+    GameOver,
+    Cleared { clear_time: Duration },
+}
+
+/// Time since the player last spawned at the start, paused while the player is frozen.
+#[derive(Default, Resource)]
+pub struct CourseTimer(pub Duration);
+
 #[derive(Component)]
 pub struct GameModeToggleButton {
     pub is_editing: bool,
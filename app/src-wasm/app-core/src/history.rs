@@ -0,0 +1,266 @@
+use crate::{
+    course::start::start_marker_pos, grid_to_world, pos_to_world, CourseLoader, CourseRes,
+    Draggable, GameMode, GoalPole, GroundTileUpdateEvent, StartMarker, TextInputFocus,
+};
+use app_config::*;
+use bevy::prelude::*;
+use shrm_core::{AreaId, TileVariant};
+use std::collections::HashMap;
+
+/// A reversible change of the course, as made in the editor.
+#[derive(Clone, Debug)]
+pub enum EditCommand {
+    PlaceTile {
+        grid_pos: [i32; 2],
+        variant: TileVariant,
+    },
+    RemoveTile {
+        grid_pos: [i32; 2],
+        variant: TileVariant,
+    },
+    MoveGoal {
+        from: i32,
+        to: i32,
+    },
+    MoveStart {
+        from: [i32; 2],
+        to: [i32; 2],
+    },
+}
+
+/// All commands of a single mouse stroke, undone and redone at once.
+#[derive(Debug)]
+struct HistoryEntry {
+    area: AreaId,
+    commands: Vec<EditCommand>,
+}
+
+#[derive(Default, Resource)]
+pub struct EditHistory {
+    undo: Vec<HistoryEntry>,
+    redo: Vec<HistoryEntry>,
+    stroke: Option<HistoryEntry>,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum HistoryEvent {
+    Undo,
+    Redo,
+}
+
+impl EditHistory {
+    /// Adds `command` to the current stroke, which ends once all mouse buttons are released.
+    pub fn record(&mut self, area: AreaId, command: EditCommand) {
+        if let Some(stroke) = &mut self.stroke {
+            if stroke.area == area {
+                // a drag moves the goal and the start one step at a time
+                match (stroke.commands.last_mut(), &command) {
+                    (
+                        Some(EditCommand::MoveGoal { to, .. }),
+                        EditCommand::MoveGoal { from, to: next },
+                    ) if to == from => {
+                        *to = *next;
+                        return;
+                    }
+                    (
+                        Some(EditCommand::MoveStart { to, .. }),
+                        EditCommand::MoveStart { from, to: next },
+                    ) if to == from => {
+                        *to = *next;
+                        return;
+                    }
+                    _ => {}
+                }
+                stroke.commands.push(command);
+                return;
+            }
+        }
+        self.end_stroke();
+        self.stroke = Some(HistoryEntry {
+            area,
+            commands: vec![command],
+        });
+    }
+
+    pub fn end_stroke(&mut self) {
+        if let Some(stroke) = self.stroke.take() {
+            self.undo.push(stroke);
+            self.redo.clear();
+        }
+    }
+
+    /// Forgets all entries, since they only apply to the course they were recorded on.
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+        self.stroke = None;
+    }
+
+    /// The stack `event` takes its entry from and the one it pushes it to.
+    fn stacks(&mut self, event: HistoryEvent) -> (&mut Vec<HistoryEntry>, &mut Vec<HistoryEntry>) {
+        match event {
+            HistoryEvent::Undo => (&mut self.undo, &mut self.redo),
+            HistoryEvent::Redo => (&mut self.redo, &mut self.undo),
+        }
+    }
+}
+
+pub(crate) fn clear_history(mut history: ResMut<EditHistory>) {
+    history.clear();
+}
+
+pub(crate) fn send_history_events(
+    keyboard_input: Res<Input<KeyCode>>,
+    game_mode: Res<GameMode>,
+    text_input_focus: Res<TextInputFocus>,
+    mut history_events: EventWriter<HistoryEvent>,
+) {
+    if !matches!(*game_mode, GameMode::Build { is_editing: true }) || text_input_focus.0 {
+        return;
+    }
+    if !keyboard_input.any_pressed([KeyCode::LControl, KeyCode::RControl]) {
+        return;
+    }
+    let shift = keyboard_input.any_pressed([KeyCode::LShift, KeyCode::RShift]);
+    if keyboard_input.just_pressed(KeyCode::Z) {
+        history_events.send(if shift {
+            HistoryEvent::Redo
+        } else {
+            HistoryEvent::Undo
+        });
+    } else if keyboard_input.just_pressed(KeyCode::Y) {
+        history_events.send(HistoryEvent::Redo);
+    }
+}
+
+pub(crate) fn end_stroke(
+    mut history: ResMut<EditHistory>,
+    mouse_button_input: Res<Input<MouseButton>>,
+) {
+    if history.stroke.is_some()
+        && !mouse_button_input.any_pressed([MouseButton::Left, MouseButton::Right])
+    {
+        history.end_stroke();
+    }
+}
+
+/// Undoes or redoes the latest entry.
+///
+/// If the entry belongs to the other area, that area is loaded first
+/// and the entry is applied on the next frame, once its entities exist.
+#[allow(clippy::type_complexity)]
+pub(crate) fn apply_history(
+    mut history_events: EventReader<HistoryEvent>,
+    mut pending: Local<Option<HistoryEvent>>,
+    mut course: ResMut<CourseRes>,
+    mut course_loader: CourseLoader,
+    goal_query: Query<Entity, (With<GoalPole>, Without<Draggable>)>,
+    mut drag_query: Query<&mut Transform, (With<GoalPole>, With<Draggable>)>,
+    mut marker_query: Query<&mut Transform, (With<StartMarker>, Without<GoalPole>)>,
+) {
+    let event = if let Some(event) = pending.take().or(history_events.iter().last().copied()) {
+        event
+    } else {
+        return;
+    };
+    course_loader.history.end_stroke();
+
+    let entry = if let Some(entry) = course_loader.history.stacks(event).0.pop() {
+        entry
+    } else {
+        return;
+    };
+    if entry.area != course.area {
+        course_loader.switch_area(&mut course, entry.area);
+        course_loader.history.stacks(event).0.push(entry);
+        *pending = Some(event);
+        return;
+    }
+
+    let mut events = HashMap::new();
+    let mut goal_moved = false;
+    let mut start_moved = false;
+    let mut apply = |command: &EditCommand, undo: bool| match (command, undo) {
+        (EditCommand::PlaceTile { grid_pos, variant }, false)
+        | (EditCommand::RemoveTile { grid_pos, variant }, true) => {
+            course.spawn_tile(&mut course_loader.commands, grid_pos, variant, &mut events);
+        }
+        (EditCommand::PlaceTile { grid_pos, .. }, true)
+        | (EditCommand::RemoveTile { grid_pos, .. }, false) => {
+            remove_tile(&mut course, &mut course_loader, grid_pos, &mut events);
+        }
+        (EditCommand::MoveGoal { from, to }, undo) => {
+            // like dragging, this clears the whole ground below the goal,
+            // which gets respawned once the entry is applied
+            for x in course.goal_pos_x..course.right_edge() {
+                for y in 0..FIXED_GROUND_HEIGHT {
+                    remove_tile(&mut course, &mut course_loader, &[x, y], &mut events);
+                }
+            }
+            course.goal_pos_x = if undo { *from } else { *to };
+            goal_moved = true;
+        }
+        (EditCommand::MoveStart { from, to }, undo) => {
+            // the start ground gets respawned once the entry is applied
+            for grid_pos in course.start_area().collect::<Vec<_>>() {
+                remove_tile(&mut course, &mut course_loader, &grid_pos, &mut events);
+            }
+            course.start_pos = if undo { *from } else { *to };
+            start_moved = true;
+        }
+    };
+    match event {
+        HistoryEvent::Undo => entry
+            .commands
+            .iter()
+            .rev()
+            .for_each(|command| apply(command, true)),
+        HistoryEvent::Redo => entry
+            .commands
+            .iter()
+            .for_each(|command| apply(command, false)),
+    }
+    for event in events.into_values() {
+        course_loader.ground_tile_update_events.send(event);
+    }
+    if goal_moved {
+        for entity in goal_query.iter() {
+            course_loader.commands.entity(entity).despawn_recursive();
+        }
+        if let Ok(mut transform) = drag_query.get_single_mut() {
+            transform.translation.x = pos_to_world(course.goal_pos_x + 1);
+        }
+        course.spawn_goal(
+            &mut course_loader.commands,
+            &course_loader.object_sprite_handles,
+            &mut course_loader.ground_tile_update_events,
+        );
+    }
+    if start_moved {
+        if let Ok(mut transform) = marker_query.get_single_mut() {
+            let world_pos = grid_to_world(&start_marker_pos(course.start_pos));
+            transform.translation.x = world_pos.x;
+            transform.translation.y = world_pos.y;
+        }
+        course.spawn_start(
+            &mut course_loader.commands,
+            &mut course_loader.ground_tile_update_events,
+        );
+    }
+    course_loader.history.stacks(event).1.push(entry);
+}
+
+fn remove_tile(
+    course: &mut CourseRes,
+    course_loader: &mut CourseLoader,
+    grid_pos: &[i32; 2],
+    events: &mut HashMap<Entity, GroundTileUpdateEvent>,
+) {
+    if let Some(tile) = course.tiles.remove(grid_pos) {
+        course.retile_around(grid_pos, events);
+        course_loader
+            .commands
+            .entity(tile.entity)
+            .despawn_recursive();
+    }
+}
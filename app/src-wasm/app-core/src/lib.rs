@@ -1,66 +1,101 @@
 #![allow(clippy::needless_question_mark)]
 
+mod autosave;
 mod course;
 mod drag;
 mod game_mode;
+mod history;
 mod player_sprites;
+mod replay;
 mod tile;
 mod utils;
 
+pub use autosave::{load_autosave, save_autosave};
 pub use course::{
-    get_surrounding_matrix,
-    goal_pole::{GoalPole, GoalPoleDragDirection, GoalPoleDragEvent, GoalPoleDragTimer},
+    goal_pole::{
+        CourseClearedEvent, GoalPole, GoalPoleDragDirection, GoalPoleDragEvent, GoalPoleDragTimer,
+        GoalSensor,
+    },
+    loader::CourseLoader,
     object::ObjectVariant,
     sprites::{
-        ObjectSpriteHandles, ThemeSpriteHandles, TileSpriteHandles, TileSpriteHandlesTransparent,
-        UiButtonSpriteHandles,
+        ObjectSpriteHandles, ThemeSpriteHandles, ThemedTileSpriteHandles, TileSpriteHandles,
+        TileSpriteHandlesTransparent, UiButtonSpriteHandles,
     },
+    start::{StartMarker, StartMarkerDragEvent},
     tile::{
-        GroundSurroundingMatrix, SelectedTile, Tile, TileComponent, TileNotEditable,
-        TilePlacePreview, TilePreview,
+        EditorTool, SelectedTile, Tile, TileCollider, TileComponent, TileNotEditable,
+        TilePlacePreview, TilePreview, TileSprite, TilesLifted, ToolStart,
     },
     ui_button::UiButtonVariant,
-    CourseLoading, CourseRes,
+    ChangeThemeEvent, CourseLoading, CourseRes, SwitchAreaEvent,
 };
 pub use drag::{DragEvent, DragEventFlags, Draggable, Dragging};
 pub use game_mode::{
-    GameMode, GameModeEdit, GameModeToggleButton, GameModeToggleButtonImage, GameModeToggleEvent,
+    CourseTimer, GameMode, GameModeBuild, GameModeEdit, GameModeToggleButton,
+    GameModeToggleButtonImage, GameModeToggleEvent, PlayModeEvent, PlaySession, PlayState,
 };
-pub use player_sprites::{PlayerFrame, PlayerSpriteHandles};
+pub use history::{EditCommand, EditHistory, HistoryEvent};
+pub use player_sprites::{load_player_sprites, PlayerFrame, PlayerSpriteHandles};
+pub use replay::{InputReplay, ReplayMode};
 use tile::update_ground_tile;
 pub use tile::{DespawnTileEvent, GroundTileUpdateEvent, SpawnTileEvent};
 pub use utils::*;
 
-use bevy::{asset::LoadState, prelude::*};
+use app_config::*;
+use autosave::{autosave, Autosave};
+use bevy::{asset::LoadState, prelude::*, time::FixedTimestep};
 use course::{
     goal_pole::{move_goal_pole, respawn_goal_pole, RespawnGoalPoleEvent},
     sprites::load_course_sprites,
+    start::{move_start_marker, respawn_start, RespawnStartEvent},
 };
 use drag::{drag_mouse_button, drag_mouse_motion, handle_drag_events};
-use player_sprites::load_player_sprites;
+use history::{apply_history, clear_history, end_stroke, send_history_events};
 use std::sync::{Arc, RwLock};
 
 #[derive(Component, Debug)]
 pub struct Ground;
 
+/// Whether a text field is focused, which takes all keyboard input.
+#[derive(Default, Resource)]
+pub struct TextInputFocus(pub bool);
+
 pub struct CorePlugin;
 
 impl Plugin for CorePlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<PlayerSpriteHandles>()
             .init_resource::<TileSpriteHandles>()
+            .init_resource::<ThemedTileSpriteHandles>()
             .init_resource::<TileSpriteHandlesTransparent>()
             .init_resource::<ThemeSpriteHandles>()
             .init_resource::<UiButtonSpriteHandles>()
             .init_resource::<ObjectSpriteHandles>()
             .init_resource::<SelectedTile>()
+            .init_resource::<EditorTool>()
+            .init_resource::<ToolStart>()
+            .init_resource::<TilesLifted>()
+            .init_resource::<TextInputFocus>()
             .init_resource::<Dragging>()
+            .init_resource::<CourseTimer>()
+            .init_resource::<EditHistory>()
+            .init_resource::<Autosave>()
+            .init_resource::<InputReplay>()
             .insert_resource(TilePlacePreview(None))
             .insert_resource(CourseLoading(Arc::new(RwLock::new(None))))
             .add_event::<GameModeToggleEvent>()
+            .add_event::<PlayModeEvent>()
             .add_event::<DragEvent>()
             .add_event::<GoalPoleDragEvent>()
+            .add_event::<CourseClearedEvent>()
             .add_event::<RespawnGoalPoleEvent>()
+            .add_event::<StartMarkerDragEvent>()
+            .add_event::<RespawnStartEvent>()
+            .add_event::<SwitchAreaEvent>()
+            .add_event::<ChangeThemeEvent>()
+            .add_event::<HistoryEvent>()
+            .add_event::<MoveCameraEvent>()
             .add_stage_after(
                 CoreStage::First,
                 AppStage::PlayerInput,
@@ -71,6 +106,13 @@ impl Plugin for CorePlugin {
                 AppStage::PrePhysics,
                 SystemStage::parallel(),
             )
+            .add_stage_after(
+                CoreStage::Update,
+                AppStage::PlayerStep,
+                SystemStage::parallel().with_run_criteria(
+                    FixedTimestep::step(PLAYER_TIMESTEP as f64).with_label(PLAYER_TIMESTEP_LABEL),
+                ),
+            )
             .add_stage_after(
                 CoreStage::PostUpdate,
                 AppStage::StateChange,
@@ -83,14 +125,21 @@ impl Plugin for CorePlugin {
             )
             .add_system_set_to_stage(AppStage::PlayerInput, State::<AppState>::get_driver())
             .add_system_set_to_stage(AppStage::PrePhysics, State::<AppState>::get_driver())
+            .add_system_set_to_stage(AppStage::PlayerStep, State::<AppState>::get_driver())
             .add_system_set_to_stage(AppStage::StateChange, State::<AppState>::get_driver())
             .add_system_set_to_stage(AppStage::TileSpawning, State::<AppState>::get_driver())
             .add_startup_system_to_stage(StartupStage::Startup, load_player_sprites)
             .add_startup_system_to_stage(StartupStage::Startup, load_course_sprites)
+            // courses entering the game are loaded outside of `CourseLoader`
+            .add_system_set(SystemSet::on_enter(AppState::Game).with_system(clear_history))
             .add_system_set_to_stage(
                 CoreStage::PreUpdate,
                 SystemSet::on_update(AppState::Game).with_system(drag_mouse_button),
             )
+            .add_system_set_to_stage(
+                CoreStage::PreUpdate,
+                SystemSet::on_update(AppState::Game).with_system(send_history_events),
+            )
             .add_system_set_to_stage(
                 CoreStage::Update,
                 SystemSet::on_update(AppState::Game).with_system(drag_mouse_motion),
@@ -103,17 +152,23 @@ impl Plugin for CorePlugin {
                 AppStage::TileSpawning,
                 SystemSet::on_update(AppState::Game)
                     .with_system(move_goal_pole)
+                    .with_system(move_start_marker)
+                    .with_system(apply_history)
                     .before(AppLabel::DespawnTile),
             )
             .add_system_set_to_stage(
                 AppStage::TileSpawning,
                 SystemSet::on_update(AppState::Game)
                     .with_system(respawn_goal_pole)
+                    .with_system(respawn_start)
                     .after(AppLabel::DespawnTile),
             )
             .add_system_set_to_stage(
                 CoreStage::Last,
-                SystemSet::on_update(AppState::Game).with_system(update_ground_tile),
+                SystemSet::on_update(AppState::Game)
+                    .with_system(update_ground_tile)
+                    .with_system(end_stroke)
+                    .with_system(autosave),
             )
             .add_system_set(SystemSet::on_exit(AppState::Load).with_system(update_ground_tile))
             .add_system_set(SystemSet::on_update(AppState::Setup).with_system(check_textures));
@@ -138,6 +193,8 @@ pub enum AppLabel {
 pub enum AppStage {
     PlayerInput,
     PrePhysics,
+    /// Runs the player simulation once per elapsed [`PLAYER_TIMESTEP`].
+    PlayerStep,
     StateChange,
     TileSpawning,
 }
@@ -18,7 +18,7 @@ pub enum PlayerFrame {
     Turn,
 }
 
-pub(crate) fn load_player_sprites(
+pub fn load_player_sprites(
     mut sprite_handles: ResMut<PlayerSpriteHandles>,
     asset_server: Res<AssetServer>,
 ) {
@@ -0,0 +1,81 @@
+use bevy::prelude::*;
+use shrm_core::{Course, InputFrame, Replay};
+
+/// Records the player input of a play session or feeds a recorded one back in.
+#[derive(Default, Resource)]
+pub struct InputReplay {
+    pub mode: ReplayMode,
+    /// The course the frames belong to.
+    pub course: Option<Course>,
+    pub frames: Vec<InputFrame>,
+}
+
+#[derive(Default)]
+pub enum ReplayMode {
+    #[default]
+    Idle,
+    Recording,
+    Playback {
+        frame: usize,
+    },
+}
+
+impl InputReplay {
+    pub fn record(course: Course) -> Self {
+        Self {
+            mode: ReplayMode::Recording,
+            course: Some(course),
+            frames: vec![],
+        }
+    }
+
+    pub fn play(replay: Replay) -> Self {
+        Self {
+            mode: ReplayMode::Playback { frame: 0 },
+            course: Some(replay.course),
+            frames: replay.frames,
+        }
+    }
+
+    /// Starts the recording or playback over from the first frame.
+    pub fn restart(&mut self) {
+        match &mut self.mode {
+            ReplayMode::Idle => {}
+            ReplayMode::Recording => self.frames.clear(),
+            ReplayMode::Playback { frame } => *frame = 0,
+        }
+    }
+
+    /// Stops recording or playback, but keeps the frames so that they can still be saved.
+    pub fn stop(&mut self) {
+        self.mode = ReplayMode::Idle;
+    }
+
+    pub fn is_playing_back(&self) -> bool {
+        matches!(self.mode, ReplayMode::Playback { .. })
+    }
+
+    /// Returns the input of the current frame during playback and records `input` otherwise.
+    pub fn next_frame(&mut self, input: InputFrame) -> InputFrame {
+        match &mut self.mode {
+            ReplayMode::Idle => input,
+            ReplayMode::Recording => {
+                self.frames.push(input);
+                input
+            }
+            ReplayMode::Playback { frame } => {
+                // the player stands still once the recording ran out
+                let input = self.frames.get(*frame).copied().unwrap_or_default();
+                *frame += 1;
+                input
+            }
+        }
+    }
+
+    pub fn to_replay(&self) -> Option<Replay> {
+        self.course.as_ref().map(|course| Replay {
+            course: course.clone(),
+            frames: self.frames.clone(),
+        })
+    }
+}
@@ -1,3 +1,4 @@
+use crate::TileSprite;
 use bevy::prelude::*;
 use shrm_core::TileVariant;
 
@@ -18,15 +19,17 @@ pub struct GroundTileUpdateEvent {
 }
 
 pub(crate) fn update_ground_tile(
-    mut query: Query<&mut TextureAtlasSprite>,
-    mut child_query: Query<&Children>,
+    mut query: Query<&mut TextureAtlasSprite, With<TileSprite>>,
+    child_query: Query<&Children>,
     mut events: EventReader<GroundTileUpdateEvent>,
 ) {
     for GroundTileUpdateEvent { entity, index } in events.iter() {
-        if let Ok(children) = child_query.get_mut(*entity) {
-            let child = children[1];
-            let mut sprite = query.get_mut(child).unwrap();
-            *sprite = TextureAtlasSprite::new(*index);
+        if let Ok(children) = child_query.get(*entity) {
+            for child in children.iter() {
+                if let Ok(mut sprite) = query.get_mut(*child) {
+                    *sprite = TextureAtlasSprite::new(*index);
+                }
+            }
         }
     }
 }
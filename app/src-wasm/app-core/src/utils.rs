@@ -3,6 +3,9 @@ use bevy::{prelude::*, render::primitives::Frustum};
 
 pub type MainCameraQuery<'w, 's, 'q> = Query<'w, 's, (&'q Transform, &'q Camera), With<Frustum>>;
 
+/// Centers the camera of the editor on the given world position.
+pub struct MoveCameraEvent(pub Vec2);
+
 #[inline]
 pub fn pos_to_world(pos: i32) -> f32 {
     pos as f32 * GRID_SIZE * RAPIER_SCALE
@@ -51,3 +54,8 @@ pub fn cursor_to_world(cursor: Vec2, camera_query: &MainCameraQuery, window: &Wi
 
     [cursor_pos_far.truncate().x, cursor_pos_far.truncate().y]
 }
+
+/// Milliseconds since the Unix epoch.
+pub fn now_millis() -> u64 {
+    js_sys::Date::now() as u64
+}
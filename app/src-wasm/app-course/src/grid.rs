@@ -1,43 +1,79 @@
 use app_config::*;
-use app_core::GameModeToggleEvent;
+use app_core::{CourseRes, GameMode};
 use bevy::prelude::*;
 
 #[derive(Component)]
-pub(crate) struct Grid;
+pub(crate) struct Grid {
+    width: i32,
+    height: i32,
+}
 
-pub fn setup_grid(mut commands: Commands) {
-    for x in 0..MAX_COURSE_X {
-        for y in 0..MAX_COURSE_Y {
-            commands.spawn((
-                SpriteBundle {
-                    sprite: Sprite {
-                        color: Color::rgba(0.25, 0.25, 0.25, 0.7),
-                        custom_size: Some(Vec2::new(TILE_SIZE, TILE_SIZE)),
-                        ..default()
-                    },
-                    transform: Transform {
-                        translation: Vec3::new(
-                            x as f32 * GRID_SIZE * RAPIER_SCALE + GRID_SIZE * RAPIER_SCALE / 2.,
-                            y as f32 * GRID_SIZE * RAPIER_SCALE + GRID_SIZE * RAPIER_SCALE / 2.,
-                            Z_INDEX_GRID,
-                        ),
+pub fn setup_grid(mut commands: Commands, course: Res<CourseRes>) {
+    spawn_grid(&mut commands, course.width, course.height, true);
+}
+
+fn spawn_grid(commands: &mut Commands, width: i32, height: i32, is_visible: bool) {
+    commands
+        .spawn((
+            SpatialBundle {
+                visibility: Visibility { is_visible },
+                ..default()
+            },
+            Grid { width, height },
+        ))
+        .with_children(|parent| {
+            for x in 0..width {
+                for y in 0..height {
+                    parent.spawn(SpriteBundle {
+                        sprite: Sprite {
+                            color: Color::rgba(0.25, 0.25, 0.25, 0.7),
+                            custom_size: Some(Vec2::new(TILE_SIZE, TILE_SIZE)),
+                            ..default()
+                        },
+                        transform: Transform {
+                            translation: Vec3::new(
+                                x as f32 * GRID_SIZE * RAPIER_SCALE + GRID_SIZE * RAPIER_SCALE / 2.,
+                                y as f32 * GRID_SIZE * RAPIER_SCALE + GRID_SIZE * RAPIER_SCALE / 2.,
+                                Z_INDEX_GRID,
+                            ),
+                            ..default()
+                        },
                         ..default()
-                    },
-                    ..default()
-                },
-                Grid,
-            ));
-        }
-    }
+                    });
+                }
+            }
+        });
 }
 
-pub(crate) fn toggle_grid(
-    mut query: Query<&mut Visibility, With<Grid>>,
-    mut game_mode_toggle_event: EventReader<GameModeToggleEvent>,
+/// Respawns the grid whenever the size of the loaded area changes.
+pub(crate) fn resize_grid(
+    mut commands: Commands,
+    query: Query<(Entity, &Grid, &Visibility)>,
+    course: Res<CourseRes>,
 ) {
-    for event in game_mode_toggle_event.iter() {
-        for mut visibility in query.iter_mut() {
-            visibility.is_visible = event.is_editing;
+    if !course.is_changed() {
+        return;
+    }
+    if let Ok((entity, grid, visibility)) = query.get_single() {
+        if grid.width != course.width || grid.height != course.height {
+            commands.entity(entity).despawn_recursive();
+            spawn_grid(
+                &mut commands,
+                course.width,
+                course.height,
+                visibility.is_visible,
+            );
         }
     }
 }
+
+/// Shows the grid only while editing.
+pub(crate) fn toggle_grid(mut query: Query<&mut Visibility, With<Grid>>, game_mode: Res<GameMode>) {
+    if !game_mode.is_changed() {
+        return;
+    }
+    let is_editing = matches!(*game_mode, GameMode::Build { is_editing: true });
+    for mut visibility in query.iter_mut() {
+        visibility.is_visible = is_editing;
+    }
+}
@@ -2,14 +2,18 @@ mod grid;
 
 use app_config::*;
 use app_core::{
-    AppLabel, AppStage, AppState, CourseRes, DespawnTileEvent, GameMode, GroundTileUpdateEvent,
-    SelectedTile, SpawnTileEvent, ThemeSpriteHandles, Tile, TileNotEditable,
+    AppLabel, AppStage, AppState, ChangeThemeEvent, CourseLoader, CourseRes, DespawnTileEvent,
+    EditCommand, EditHistory, GameMode, GroundTileUpdateEvent, SelectedTile, SpawnTileEvent,
+    SwitchAreaEvent, ThemeSpriteHandles, TileNotEditable, TileSprite,
 };
 use bevy::prelude::*;
-use grid::{setup_grid, toggle_grid};
+use grid::{resize_grid, setup_grid, toggle_grid};
 use shrm_core::{GroundVariant, ThemeVariant, TileVariant};
 use std::collections::HashMap;
 
+#[derive(Component)]
+struct Background(ThemeVariant);
+
 pub struct CoursePlugin;
 
 impl Plugin for CoursePlugin {
@@ -20,7 +24,13 @@ impl Plugin for CoursePlugin {
                 .with_system(setup)
                 .with_system(setup_grid),
         )
-        .add_system_set(SystemSet::on_update(AppState::Game).with_system(toggle_grid))
+        .add_system_set(
+            SystemSet::on_update(AppState::Game)
+                .with_system(toggle_grid)
+                .with_system(resize_grid)
+                .with_system(change_theme)
+                .with_system(update_background.after(change_theme)),
+        )
         .add_system_set_to_stage(
             AppStage::TileSpawning,
             SystemSet::on_update(AppState::Game)
@@ -32,49 +42,109 @@ impl Plugin for CoursePlugin {
             SystemSet::on_update(AppState::Game)
                 .with_system(despawn_tile)
                 .label(AppLabel::DespawnTile),
+        )
+        .add_system_set_to_stage(
+            AppStage::TileSpawning,
+            SystemSet::on_update(AppState::Game)
+                .with_system(switch_area)
+                .after(AppLabel::DespawnTile),
         );
     }
 }
 
-fn setup(
-    mut commands: Commands,
-    mut selected_tile: ResMut<SelectedTile>,
-    theme_sprite_handles: Res<ThemeSpriteHandles>,
-) {
+fn setup(mut commands: Commands, mut selected_tile: ResMut<SelectedTile>) {
     commands.insert_resource(GameMode::Build { is_editing: true });
     selected_tile.0 = Some(TileVariant::Ground(GroundVariant::default()));
+}
 
-    let texture = theme_sprite_handles
-        .0
-        .get(&ThemeVariant::Plain)
-        .unwrap()
-        .clone();
+fn spawn_background(
+    commands: &mut Commands,
+    theme_sprite_handles: &ThemeSpriteHandles,
+    theme: &ThemeVariant,
+) {
+    let texture = theme_sprite_handles.0.get(theme).unwrap().clone();
 
     let scale = 2.5;
     let image_size = 512.;
     let offset = -50.;
     for i in (1..=15).step_by(2) {
-        commands.spawn(SpriteBundle {
-            texture: texture.clone(),
-            transform: Transform {
-                translation: Vec3::new(
-                    scale / 2. * image_size * (i as f32) + offset,
-                    image_size,
-                    Z_INDEX_BACKGROUND,
-                ),
-                scale: Vec3::new(scale, scale, 0.),
+        commands.spawn((
+            SpriteBundle {
+                texture: texture.clone(),
+                transform: Transform {
+                    translation: Vec3::new(
+                        scale / 2. * image_size * (i as f32) + offset,
+                        image_size,
+                        Z_INDEX_BACKGROUND,
+                    ),
+                    scale: Vec3::new(scale, scale, 0.),
+                    ..Default::default()
+                },
                 ..Default::default()
             },
-            ..Default::default()
-        });
+            Background(theme.clone()),
+        ));
+    }
+}
+
+fn switch_area(
+    mut course: ResMut<CourseRes>,
+    mut switch_area_events: EventReader<SwitchAreaEvent>,
+    mut course_loader: CourseLoader,
+) {
+    if let Some(SwitchAreaEvent(area)) = switch_area_events.iter().last() {
+        course_loader.switch_area(&mut course, *area);
     }
 }
 
+fn change_theme(
+    mut course: ResMut<CourseRes>,
+    mut change_theme_events: EventReader<ChangeThemeEvent>,
+    asset_server: Res<AssetServer>,
+    mut texture_atlases: ResMut<Assets<TextureAtlas>>,
+    child_query: Query<&Children>,
+    mut atlas_query: Query<&mut Handle<TextureAtlas>, With<TileSprite>>,
+) {
+    if let Some(ChangeThemeEvent(theme)) = change_theme_events.iter().last() {
+        if *theme != course.theme {
+            course.set_theme(
+                theme.clone(),
+                &asset_server,
+                &mut texture_atlases,
+                &child_query,
+                &mut atlas_query,
+            );
+        }
+    }
+}
+
+/// Respawns the background whenever the theme of the loaded area changes.
+fn update_background(
+    mut commands: Commands,
+    query: Query<(Entity, &Background)>,
+    course: Res<CourseRes>,
+    theme_sprite_handles: Res<ThemeSpriteHandles>,
+) {
+    if !course.is_changed() {
+        return;
+    }
+    if let Some((_, Background(theme))) = query.iter().next() {
+        if *theme == course.theme {
+            return;
+        }
+    }
+    for (entity, _) in query.iter() {
+        commands.entity(entity).despawn();
+    }
+    spawn_background(&mut commands, &theme_sprite_handles, &course.theme);
+}
+
 fn spawn_tile(
     mut commands: Commands,
     mut course: ResMut<CourseRes>,
     mut spawn_tile_events: EventReader<SpawnTileEvent>,
     mut ground_tile_update_events: EventWriter<GroundTileUpdateEvent>,
+    mut history: ResMut<EditHistory>,
 ) {
     let mut events = HashMap::new();
     for SpawnTileEvent {
@@ -82,7 +152,19 @@ fn spawn_tile(
         tile_variant,
     } in spawn_tile_events.iter()
     {
+        if course.tiles.contains_key(grid_pos) {
+            continue;
+        }
         course.spawn_tile(&mut commands, grid_pos, tile_variant, &mut events);
+        if course.tiles.contains_key(grid_pos) {
+            history.record(
+                course.area,
+                EditCommand::PlaceTile {
+                    grid_pos: *grid_pos,
+                    variant: tile_variant.clone(),
+                },
+            );
+        }
     }
     for event in events.into_values() {
         ground_tile_update_events.send(event);
@@ -95,36 +177,30 @@ fn despawn_tile(
     mut despawn_tile_events: EventReader<DespawnTileEvent>,
     mut test_query: Query<Entity, Without<TileNotEditable>>,
     mut ground_tile_update_events: EventWriter<GroundTileUpdateEvent>,
+    mut history: ResMut<EditHistory>,
 ) {
+    let mut events = HashMap::new();
     for DespawnTileEvent { grid_pos, force } in despawn_tile_events.iter() {
         if let Some(tile) = course.tiles.remove(grid_pos) {
             if !force && test_query.get_mut(tile.entity).is_err() {
                 course.tiles.insert(*grid_pos, tile);
                 continue;
             }
-            for x in grid_pos[0] - 1..=grid_pos[0] + 1 {
-                for y in grid_pos[1] - 1..=grid_pos[1] + 1 {
-                    let pos = [x, y];
-                    if &pos == grid_pos {
-                        continue;
-                    }
-                    if let Some(Tile {
-                        entity,
-                        variant: TileVariant::Ground(ground_variant),
-                        mtrx: Some(mtrx),
-                    }) = course.tiles.get_mut(&pos)
-                    {
-                        mtrx.0[(y - grid_pos[1] + 1) as usize][(grid_pos[0] - x + 1) as usize] =
-                            false;
-                        *ground_variant = GroundVariant::from_surrounding_matrix(&mtrx.0);
-                        ground_tile_update_events.send(GroundTileUpdateEvent {
-                            entity: *entity,
-                            index: ground_variant.get_sprite_sheet_index(),
-                        });
-                    }
-                }
+            // forced removals belong to goal and start moves, which record themselves
+            if !force {
+                history.record(
+                    course.area,
+                    EditCommand::RemoveTile {
+                        grid_pos: *grid_pos,
+                        variant: tile.variant.clone(),
+                    },
+                );
             }
+            course.retile_around(grid_pos, &mut events);
             commands.entity(tile.entity).despawn_recursive();
         }
     }
+    for event in events.into_values() {
+        ground_tile_update_events.send(event);
+    }
 }
@@ -1,30 +1,168 @@
 use app_config::*;
-use app_core::{pos_to_world, CourseRes, GoalPoleDragDirection, GoalPoleDragEvent};
-use app_player::Player;
+use app_core::{
+    grid_to_world, pos_to_world, CourseRes, Dragging, GameMode, GoalPoleDragDirection,
+    GoalPoleDragEvent, MoveCameraEvent, SwitchAreaEvent,
+};
+use app_player::{Player, PlayerInterpolation, PlayerVelocity};
 use bevy::{
+    input::mouse::{MouseScrollUnit, MouseWheel},
     prelude::*,
     render::{camera::Camera, primitives::Frustum},
 };
+use shrm_core::{AreaId, PLAYER_START_OFFSET};
+
+/// Zoom of the camera while editing, kept while testing the course with the default zoom.
+#[derive(Resource)]
+pub struct EditorZoom(f32);
+
+impl Default for EditorZoom {
+    fn default() -> Self {
+        Self(CAMERA_SCALE)
+    }
+}
 
+/// Follows the player, which is an invisible anchor of the camera while editing.
+#[allow(clippy::type_complexity)]
 pub fn position_camera(
     mut query: Query<&mut Transform, (With<Camera>, With<Frustum>)>,
-    player_query: Query<&Transform, (With<Player>, Without<Camera>)>,
+    mut player_query: Query<
+        (&mut Transform, &PlayerInterpolation),
+        (With<Player>, Without<Camera>),
+    >,
     course: Res<CourseRes>,
+    game_mode: Res<GameMode>,
+    editor_zoom: Res<EditorZoom>,
 ) {
     if let Ok(mut transform) = query.get_single_mut() {
-        if let Ok(rb_transform) = player_query.get_single() {
-            transform.translation.x = rb_transform.translation.x;
-            if transform.translation.x < CAMERA_MIN_X {
-                transform.translation.x = CAMERA_MIN_X;
-            }
-            let max_pos_x = pos_to_world(course.goal_pos_x);
-            if transform.translation.x > max_pos_x {
-                transform.translation.x = max_pos_x;
+        if let Ok((mut rb_transform, interpolation)) = player_query.get_single_mut() {
+            let is_editing = matches!(*game_mode, GameMode::Build { is_editing: true });
+            let scale = if is_editing {
+                editor_zoom.0
+            } else {
+                CAMERA_SCALE
+            };
+            transform.scale = Vec3::new(scale, scale, 1.);
+
+            // the camera follows the rendered and not the simulated player
+            let pos = clamp_camera(
+                rb_transform.translation.truncate() + interpolation.offset,
+                scale,
+                &course,
+            );
+            transform.translation.x = pos.x;
+            transform.translation.y = pos.y;
+
+            // otherwise panning back from beyond the course edge would not move the camera
+            if is_editing && rb_transform.translation.truncate() != pos {
+                rb_transform.translation.x = pos.x;
+                rb_transform.translation.y = pos.y;
             }
         }
     }
 }
 
+fn clamp_camera(pos: Vec2, scale: f32, course: &CourseRes) -> Vec2 {
+    let zoom = scale / CAMERA_SCALE;
+    let (half_width, half_height) = (CAMERA_MIN_X * zoom, CAMERA_MIN_Y * zoom);
+    // the right edge of the view stays where it is at the default zoom
+    let max_x = (pos_to_world(course.right_edge() - MAX_COURSE_GOAL_OFFSET_X) + CAMERA_MIN_X
+        - half_width)
+        .max(half_width);
+    let max_y = (pos_to_world(course.height) - half_height).max(half_height);
+    Vec2::new(
+        pos.x.clamp(half_width, max_x),
+        pos.y.clamp(half_height, max_y),
+    )
+}
+
+/// Pans the camera with the middle mouse button and at the edges of the window
+/// and zooms it towards the cursor with the mouse wheel.
+#[allow(clippy::too_many_arguments)]
+#[allow(clippy::type_complexity)]
+pub fn control_editor_camera(
+    camera_query: Query<&Transform, (With<Camera>, With<Frustum>)>,
+    mut player_query: Query<&mut Transform, (With<Player>, Without<Camera>)>,
+    interaction_query: Query<&Interaction>,
+    windows: Res<Windows>,
+    mouse_button_input: Res<Input<MouseButton>>,
+    mut mouse_wheel_events: EventReader<MouseWheel>,
+    time: Res<Time>,
+    game_mode: Res<GameMode>,
+    dragging: Res<Dragging>,
+    mut editor_zoom: ResMut<EditorZoom>,
+    mut last_cursor: Local<Option<Vec2>>,
+) {
+    let wheel = mouse_wheel_events
+        .iter()
+        .map(|event| match event.unit {
+            MouseScrollUnit::Line => event.y,
+            MouseScrollUnit::Pixel => event.y / 100.,
+        })
+        .sum::<f32>();
+    let window = windows.get_primary().unwrap();
+    let cursor = window.cursor_position();
+    let last_cursor = std::mem::replace(&mut *last_cursor, cursor);
+    if !matches!(*game_mode, GameMode::Build { is_editing: true }) {
+        return;
+    }
+    let (camera_transform, mut player_transform) =
+        if let (Ok(camera_transform), Ok(player_transform)) =
+            (camera_query.get_single(), player_query.get_single_mut())
+        {
+            (camera_transform, player_transform)
+        } else {
+            return;
+        };
+    let cursor = if let Some(cursor) = cursor {
+        cursor
+    } else {
+        return;
+    };
+    let camera_pos = camera_transform.translation.truncate();
+    let scale = editor_zoom.0;
+    let mut pos = camera_pos;
+
+    let is_over_ui = interaction_query
+        .iter()
+        .any(|interaction| *interaction != Interaction::None);
+    if mouse_button_input.pressed(MouseButton::Middle) {
+        if let Some(last_cursor) = last_cursor {
+            pos -= (cursor - last_cursor) * scale;
+        }
+    } else if !is_over_ui && dragging.0.is_none() {
+        let direction = Vec2::new(
+            edge_direction(cursor.x, window.width()),
+            edge_direction(cursor.y, window.height()),
+        );
+        pos += direction * CAMERA_EDGE_PAN_SPEED * scale * time.delta_seconds();
+    }
+
+    if wheel != 0. && !is_over_ui {
+        let new_scale =
+            (scale * (1. - wheel * CAMERA_ZOOM_STEP)).clamp(CAMERA_ZOOM_MIN, CAMERA_ZOOM_MAX);
+        // the world position below the cursor stays in place
+        let cursor_world =
+            camera_pos + (cursor - Vec2::new(window.width(), window.height()) / 2.) * scale;
+        pos = cursor_world - (cursor_world - pos) * new_scale / scale;
+        editor_zoom.0 = new_scale;
+    }
+
+    if pos != camera_pos {
+        player_transform.translation.x = pos.x;
+        player_transform.translation.y = pos.y;
+    }
+}
+
+fn edge_direction(cursor: f32, size: f32) -> f32 {
+    if cursor < CAMERA_EDGE_PAN_MARGIN {
+        -1.
+    } else if cursor > size - CAMERA_EDGE_PAN_MARGIN {
+        1.
+    } else {
+        0.
+    }
+}
+
 pub fn move_player_on_goal_pole_drag(
     mut player_query: Query<&mut Transform, With<Player>>,
     mut drag_events: EventReader<GoalPoleDragEvent>,
@@ -40,10 +178,46 @@ pub fn move_player_on_goal_pole_drag(
             if transform.translation.x < CAMERA_MIN_X {
                 transform.translation.x = CAMERA_MIN_X;
             }
-            let max_pos_x = pos_to_world(course.goal_pos_x);
+            let max_pos_x = pos_to_world(course.right_edge() - MAX_COURSE_GOAL_OFFSET_X);
             if transform.translation.x > max_pos_x {
                 transform.translation.x = max_pos_x;
             }
         }
     }
 }
+
+pub fn move_player_on_area_switch(
+    mut player_query: Query<(&mut Transform, &mut PlayerVelocity), With<Player>>,
+    mut switch_area_events: EventReader<SwitchAreaEvent>,
+    course: Res<CourseRes>,
+) {
+    if let Some(SwitchAreaEvent(area)) = switch_area_events.iter().last() {
+        if let Ok((mut transform, mut vel)) = player_query.get_single_mut() {
+            let grid_pos = if *area == AreaId::Main {
+                course.player_start()
+            } else {
+                PLAYER_START_OFFSET
+            };
+            let world_pos = grid_to_world(&grid_pos);
+            transform.translation.x = world_pos.x;
+            transform.translation.y = world_pos.y;
+            vel.0 = Vec2::ZERO;
+        }
+    }
+}
+
+pub fn move_player_on_camera_move(
+    mut player_query: Query<&mut Transform, With<Player>>,
+    mut move_camera_events: EventReader<MoveCameraEvent>,
+    game_mode: Res<GameMode>,
+) {
+    if let Some(MoveCameraEvent(pos)) = move_camera_events.iter().last() {
+        if !matches!(*game_mode, GameMode::Build { is_editing: true }) {
+            return;
+        }
+        if let Ok(mut transform) = player_query.get_single_mut() {
+            transform.translation.x = pos.x;
+            transform.translation.y = pos.y;
+        }
+    }
+}
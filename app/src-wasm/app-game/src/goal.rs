@@ -0,0 +1,192 @@
+use app_config::*;
+use app_core::{
+    pos_to_world, CourseClearedEvent, CourseRes, CourseTimer, GameMode, GameModeToggleEvent,
+    GoalSensor, PlaySession, PlayState,
+};
+use app_player::{Player, PlayerFrozen, PlayerState, PlayerStateChangeEvent, PlayerVelocity};
+use bevy::{math::Vec3Swizzles, prelude::*};
+use bevy_rapier::prelude::*;
+use std::time::Duration;
+
+const SLIDE_VEL: f32 = 200.;
+const WALK_OFF_SECS: f32 = 1.5;
+
+/// Scripted movement of the player after touching the goal.
+#[derive(Component)]
+pub struct ClearSequence {
+    clear_time: Duration,
+    phase: ClearPhase,
+}
+
+enum ClearPhase {
+    Slide,
+    WalkOff(Timer),
+}
+
+pub fn tick_course_timer(
+    mut course_timer: ResMut<CourseTimer>,
+    player_query: Query<(), (With<Player>, Without<PlayerFrozen>)>,
+    game_mode: Res<GameMode>,
+    time: Res<Time>,
+) {
+    if matches!(*game_mode, GameMode::Build { is_editing: true }) || player_query.is_empty() {
+        return;
+    }
+    course_timer.0 += time.delta();
+}
+
+pub fn reset_course_timer(
+    mut course_timer: ResMut<CourseTimer>,
+    mut game_mode_toggle_events: EventReader<GameModeToggleEvent>,
+) {
+    if game_mode_toggle_events.iter().last().is_some() {
+        course_timer.0 = Duration::ZERO;
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub fn touch_goal(
+    mut commands: Commands,
+    mut player_query: Query<
+        (Entity, &Transform, &Children, &mut PlayerVelocity),
+        (With<Player>, Without<PlayerFrozen>),
+    >,
+    collider_query: Query<&Collider>,
+    sensor_query: Query<(), With<GoalSensor>>,
+    ctx: Res<RapierContext>,
+    game_mode: Res<GameMode>,
+    course_timer: Res<CourseTimer>,
+) {
+    if let GameMode::Build { is_editing: true } = *game_mode {
+        return;
+    }
+    let (entity, transform, children, mut vel) = if let Ok(player) = player_query.get_single_mut() {
+        player
+    } else {
+        return;
+    };
+    let collider = if let Some(collider) = children
+        .iter()
+        .find_map(|child| collider_query.get(*child).ok())
+    {
+        collider
+    } else {
+        return;
+    };
+
+    let mut touched = false;
+    ctx.intersections_with_shape(
+        transform.translation.xy(),
+        0.,
+        collider,
+        QueryFilter {
+            predicate: Some(&|entity| sensor_query.get(entity).is_ok()),
+            ..Default::default()
+        },
+        |_| {
+            touched = true;
+            false
+        },
+    );
+    if touched {
+        vel.0 = Vec2::ZERO;
+        commands.entity(entity).insert((
+            PlayerFrozen,
+            ClearSequence {
+                clear_time: course_timer.0,
+                phase: ClearPhase::Slide,
+            },
+        ));
+    }
+}
+
+/// Slides the player down the pole and lets it walk off to the right.
+#[allow(clippy::type_complexity)]
+pub fn clear_sequence(
+    mut commands: Commands,
+    mut player_query: Query<(Entity, &mut Player, &mut Transform, &mut ClearSequence)>,
+    course: Res<CourseRes>,
+    time: Res<Time>,
+    mut psc_events: EventWriter<PlayerStateChangeEvent>,
+    mut course_cleared_events: EventWriter<CourseClearedEvent>,
+) {
+    let (entity, mut player, mut transform, mut sequence) =
+        if let Ok(player) = player_query.get_single_mut() {
+            player
+        } else {
+            return;
+        };
+
+    let clear_time = sequence.clear_time;
+    match &mut sequence.phase {
+        ClearPhase::Slide => {
+            let ground_y = pos_to_world(FIXED_GROUND_HEIGHT);
+            transform.translation.y =
+                (transform.translation.y - SLIDE_VEL * time.delta_seconds()).max(ground_y);
+            if transform.translation.y <= ground_y {
+                player.state = PlayerState::walk();
+                psc_events.send(PlayerStateChangeEvent {
+                    state: player.state.clone(),
+                });
+                sequence.phase =
+                    ClearPhase::WalkOff(Timer::from_seconds(WALK_OFF_SECS, TimerMode::Once));
+            }
+        }
+        ClearPhase::WalkOff(timer) => {
+            timer.tick(time.delta());
+            let max_x = pos_to_world(course.right_edge() - 2);
+            transform.translation.x =
+                (transform.translation.x + LINVEL_CAP_WALK * time.delta_seconds()).min(max_x);
+            if timer.finished() {
+                commands.entity(entity).remove::<ClearSequence>();
+                course_cleared_events.send(CourseClearedEvent { clear_time });
+            }
+        }
+    }
+}
+
+pub fn on_course_cleared(
+    mut commands: Commands,
+    player_query: Query<Entity, With<Player>>,
+    mut course_cleared_events: EventReader<CourseClearedEvent>,
+    mut game_mode: ResMut<GameMode>,
+    play_session: Option<ResMut<PlaySession>>,
+    mut game_mode_toggle_events: EventWriter<GameModeToggleEvent>,
+) {
+    let clear_time =
+        if let Some(CourseClearedEvent { clear_time }) = course_cleared_events.iter().last() {
+            *clear_time
+        } else {
+            return;
+        };
+
+    match (&mut *game_mode, play_session) {
+        (GameMode::Build { is_editing }, _) => {
+            *is_editing = true;
+            game_mode_toggle_events.send(GameModeToggleEvent { is_editing: true });
+            if let Ok(entity) = player_query.get_single() {
+                commands.entity(entity).remove::<PlayerFrozen>();
+            }
+        }
+        (GameMode::Play, Some(mut play_session)) => {
+            play_session.state = PlayState::Cleared { clear_time };
+        }
+        _ => {}
+    }
+}
+
+/// Cancels a running sequence once editing resumes.
+pub fn cancel_clear_sequence(
+    mut commands: Commands,
+    player_query: Query<Entity, With<ClearSequence>>,
+    game_mode: Res<GameMode>,
+) {
+    if let GameMode::Build { is_editing: true } = *game_mode {
+        for entity in player_query.iter() {
+            commands
+                .entity(entity)
+                .remove::<ClearSequence>()
+                .remove::<PlayerFrozen>();
+        }
+    }
+}
@@ -1,27 +1,65 @@
 mod camera;
 mod game_mode;
+mod goal;
+mod play;
 
 use app_core::AppState;
+use app_player::interpolate_player;
 use bevy::prelude::*;
-use camera::{move_player_on_goal_pole_drag, position_camera};
+use camera::{
+    control_editor_camera, move_player_on_area_switch, move_player_on_camera_move,
+    move_player_on_goal_pole_drag, position_camera, EditorZoom,
+};
 use game_mode::toggle_game_mode;
+use goal::{
+    cancel_clear_sequence, clear_sequence, on_course_cleared, reset_course_timer,
+    tick_course_timer, touch_goal,
+};
+use play::{death_sequence, hide_start_marker, kill_player, toggle_play_mode};
 
 pub struct GamePlugin;
 
 impl Plugin for GamePlugin {
     fn build(&self, app: &mut App) {
-        app.add_system_set(SystemSet::on_enter(AppState::Game).with_system(clear_input))
+        app.init_resource::<EditorZoom>()
+            .add_system_set(SystemSet::on_enter(AppState::Game).with_system(clear_input))
             .add_system_set_to_stage(
                 CoreStage::PreUpdate,
                 SystemSet::on_update(AppState::Game).with_system(toggle_game_mode),
             )
+            .add_system_set(
+                SystemSet::on_update(AppState::Game)
+                    .with_system(toggle_play_mode)
+                    .with_system(control_editor_camera)
+                    .with_system(kill_player)
+                    .with_system(death_sequence.after(kill_player))
+                    .with_system(hide_start_marker),
+            )
+            .add_system_set(
+                SystemSet::on_update(AppState::Game)
+                    .with_system(reset_course_timer)
+                    .with_system(tick_course_timer.after(reset_course_timer))
+                    .with_system(touch_goal.after(tick_course_timer))
+                    .with_system(clear_sequence)
+                    .with_system(on_course_cleared.after(clear_sequence))
+                    .with_system(cancel_clear_sequence),
+            )
             .add_system_set_to_stage(
                 CoreStage::PostUpdate,
-                SystemSet::on_update(AppState::Game).with_system(position_camera),
+                SystemSet::on_update(AppState::Game)
+                    .with_system(position_camera.after(interpolate_player)),
             )
             .add_system_set_to_stage(
                 CoreStage::PostUpdate,
                 SystemSet::on_update(AppState::Game).with_system(move_player_on_goal_pole_drag),
+            )
+            .add_system_set_to_stage(
+                CoreStage::PostUpdate,
+                SystemSet::on_update(AppState::Game).with_system(move_player_on_area_switch),
+            )
+            .add_system_set_to_stage(
+                CoreStage::PostUpdate,
+                SystemSet::on_update(AppState::Game).with_system(move_player_on_camera_move),
             );
     }
 }
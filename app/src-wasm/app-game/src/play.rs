@@ -0,0 +1,216 @@
+use crate::goal::ClearSequence;
+use app_config::*;
+use app_core::{
+    grid_to_world, CourseLoader, CourseRes, CourseTimer, GameMode, GameModeBuild, GameModeEdit,
+    GameModeToggleEvent, InputReplay, PlayModeEvent, PlaySession, PlayState, StartMarker,
+    TilePlacePreview,
+};
+use app_player::{
+    KillEvent, Player, PlayerFrozen, PlayerState, PlayerStateChangeEvent, PlayerVelocity,
+};
+use bevy::prelude::*;
+use shrm_core::Course;
+
+/// Seconds the player stands still before hopping off screen.
+const DEATH_PAUSE_SECS: f32 = 0.5;
+const DEATH_HOP_VEL: f32 = 500.;
+const DEATH_HOP_GRAVITY: f32 = 1200.;
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn toggle_play_mode(
+    mut commands: Commands,
+    mut play_mode_events: EventReader<PlayModeEvent>,
+    mut game_mode: ResMut<GameMode>,
+    mut course: ResMut<CourseRes>,
+    mut course_loader: CourseLoader,
+    play_session: Option<ResMut<PlaySession>>,
+    mut ui_query: Query<&mut Style, Or<(With<GameModeBuild>, With<GameModeEdit>)>>,
+    mut player_query: Query<(Entity, &mut Player, &mut Transform, &mut PlayerVelocity)>,
+    mut tile_place_preview: ResMut<TilePlacePreview>,
+    mut game_mode_toggle_events: EventWriter<GameModeToggleEvent>,
+    mut psc_events: EventWriter<PlayerStateChangeEvent>,
+    mut input_replay: ResMut<InputReplay>,
+) {
+    let event = if let Some(event) = play_mode_events.iter().last() {
+        event
+    } else {
+        return;
+    };
+    match (event, play_session) {
+        (PlayModeEvent::Start | PlayModeEvent::Replay(_), None) => {
+            let editor_course = Course::from(&*course);
+            let original = if let PlayModeEvent::Replay(replay) = event {
+                *input_replay = InputReplay::play(replay.as_ref().clone());
+                replay.course.clone()
+            } else {
+                *input_replay = InputReplay::record(editor_course.clone());
+                editor_course.clone()
+            };
+            // always starts in the main area with a freshly built course
+            course_loader.reload(&mut course, &original);
+            commands.insert_resource(PlaySession {
+                course: original,
+                editor_course,
+                lives: PLAYER_LIVES,
+                state: PlayState::Playing,
+            });
+            *game_mode = GameMode::Play;
+
+            for mut style in ui_query.iter_mut() {
+                style.display = Display::None;
+            }
+            if let Some((entity, _)) = tile_place_preview.0 {
+                commands.entity(entity).despawn_recursive();
+                tile_place_preview.0 = None;
+            }
+        }
+        (PlayModeEvent::Stop, Some(play_session)) => {
+            course_loader.reload(&mut course, &play_session.editor_course);
+            commands.remove_resource::<PlaySession>();
+            input_replay.stop();
+
+            for mut style in ui_query.iter_mut() {
+                style.display = Display::Flex;
+            }
+            game_mode_toggle_events.send(GameModeToggleEvent { is_editing: true });
+        }
+        (PlayModeEvent::Restart, Some(mut play_session)) => {
+            course_loader.reload(&mut course, &play_session.course);
+            play_session.lives = PLAYER_LIVES;
+            play_session.state = PlayState::Playing;
+            input_replay.restart();
+        }
+        _ => return,
+    }
+
+    if let Ok((entity, mut player, mut transform, mut vel)) = player_query.get_single_mut() {
+        respawn_player(
+            &mut commands,
+            entity,
+            &mut player,
+            &mut transform,
+            &mut vel,
+            &course,
+            &mut psc_events,
+        );
+    }
+}
+
+pub fn kill_player(
+    mut commands: Commands,
+    mut kill_events: EventReader<KillEvent>,
+    mut player_query: Query<(&Transform, &mut PlayerVelocity), With<Player>>,
+    game_mode: Res<GameMode>,
+    play_session: Option<ResMut<PlaySession>>,
+) {
+    let event = kill_events.iter().last();
+    if let (Some(KillEvent(entity)), GameMode::Play, Some(mut play_session)) =
+        (event, &*game_mode, play_session)
+    {
+        if !matches!(play_session.state, PlayState::Playing) {
+            return;
+        }
+        if let Ok((transform, mut vel)) = player_query.get_mut(*entity) {
+            vel.0 = Vec2::ZERO;
+            commands.entity(*entity).insert(PlayerFrozen);
+            play_session.lives = play_session.lives.saturating_sub(1);
+            play_session.state = PlayState::Dying {
+                timer: Timer::from_seconds(DEATH_SEQUENCE_SECS, TimerMode::Once),
+                start_y: transform.translation.y,
+            };
+        }
+    }
+}
+
+/// Lets the player hop off screen and then respawns it, unless all lives are lost.
+///
+/// Like a restart, respawning rebuilds the course, so that play-time changes are undone.
+#[allow(clippy::too_many_arguments)]
+pub fn death_sequence(
+    mut commands: Commands,
+    mut player_query: Query<(Entity, &mut Player, &mut Transform, &mut PlayerVelocity)>,
+    mut course: ResMut<CourseRes>,
+    mut course_loader: CourseLoader,
+    play_session: Option<ResMut<PlaySession>>,
+    time: Res<Time>,
+    mut psc_events: EventWriter<PlayerStateChangeEvent>,
+) {
+    let mut play_session = if let Some(play_session) = play_session {
+        play_session
+    } else {
+        return;
+    };
+    let (timer, start_y) = if let PlayState::Dying { timer, start_y } = &mut play_session.state {
+        (timer, *start_y)
+    } else {
+        return;
+    };
+    let (entity, mut player, mut transform, mut vel) =
+        if let Ok(player) = player_query.get_single_mut() {
+            player
+        } else {
+            return;
+        };
+
+    timer.tick(time.delta());
+    let hop_secs = (timer.elapsed_secs() - DEATH_PAUSE_SECS).max(0.);
+    transform.translation.y =
+        start_y + DEATH_HOP_VEL * hop_secs - DEATH_HOP_GRAVITY / 2. * hop_secs * hop_secs;
+    if !timer.finished() {
+        return;
+    }
+
+    if play_session.lives == 0 {
+        play_session.state = PlayState::GameOver;
+        return;
+    }
+    play_session.state = PlayState::Playing;
+    let original = play_session.course.clone();
+    course_loader.reload(&mut course, &original);
+    respawn_player(
+        &mut commands,
+        entity,
+        &mut player,
+        &mut transform,
+        &mut vel,
+        &course,
+        &mut psc_events,
+    );
+}
+
+fn respawn_player(
+    commands: &mut Commands,
+    entity: Entity,
+    player: &mut Player,
+    transform: &mut Transform,
+    vel: &mut PlayerVelocity,
+    course: &CourseRes,
+    psc_events: &mut EventWriter<PlayerStateChangeEvent>,
+) {
+    let world_pos = grid_to_world(&course.player_start());
+    transform.translation.x = world_pos.x;
+    transform.translation.y = world_pos.y;
+    vel.0 = Vec2::ZERO;
+    player.state = PlayerState::float();
+    psc_events.send(PlayerStateChangeEvent {
+        state: player.state.clone(),
+    });
+    commands
+        .entity(entity)
+        .remove::<PlayerFrozen>()
+        .remove::<ClearSequence>();
+    commands.insert_resource(CourseTimer::default());
+}
+
+/// The start marker is an editing handle and has no place in play mode.
+pub fn hide_start_marker(
+    mut query: Query<&mut Visibility, With<StartMarker>>,
+    game_mode: Res<GameMode>,
+) {
+    let is_visible = !matches!(*game_mode, GameMode::Play);
+    for mut visibility in query.iter_mut() {
+        if visibility.is_visible != is_visible {
+            visibility.is_visible = is_visible;
+        }
+    }
+}
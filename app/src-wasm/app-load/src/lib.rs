@@ -1,5 +1,6 @@
 use app_core::{
     AppState, CourseLoading, CourseRes, GroundTileUpdateEvent, ObjectSpriteHandles, Tile,
+    TileSprite,
 };
 use bevy::prelude::*;
 use shrm_core::TileVariant;
@@ -27,35 +28,51 @@ fn check_load(
     mut loading_finished: EventWriter<LoadingFinished>,
     mut state: ResMut<State<AppState>>,
 ) {
-    if let Some(course) = &*course_loading.0.read().unwrap() {
-        let course = CourseRes::load(
-            &mut commands,
-            course,
-            &asset_server,
-            &mut texture_atlases,
-            &object_sprite_handles,
-            &mut ground_tile_update_events,
-        );
-        commands.insert_resource(course);
-        loading_finished.send(LoadingFinished);
-        state.set(AppState::Game).unwrap();
+    let course = course_loading.0.write().unwrap().take();
+    match course {
+        Some(Ok(course)) => {
+            let course = CourseRes::load(
+                &mut commands,
+                &course,
+                &asset_server,
+                &mut texture_atlases,
+                &object_sprite_handles,
+                &mut ground_tile_update_events,
+            );
+            commands.insert_resource(course);
+            loading_finished.send(LoadingFinished);
+            state.set(AppState::Game).unwrap();
+        }
+        Some(Err(reason)) => {
+            let window = web_sys::window().unwrap();
+            window.alert_with_message(&reason).unwrap();
+            state.set(AppState::Menu).unwrap();
+        }
+        None => {}
     }
 }
 
 fn update_ground_tiles(
-    mut query: Query<&mut TextureAtlasSprite>,
-    mut child_query: Query<&Children>,
-    course: Res<CourseRes>,
+    mut query: Query<&mut TextureAtlasSprite, With<TileSprite>>,
+    child_query: Query<&Children>,
+    course: Option<Res<CourseRes>>,
 ) {
+    let course = if let Some(course) = course {
+        course
+    } else {
+        return;
+    };
     for Tile {
         entity, variant, ..
     } in course.tiles.values()
     {
         if let TileVariant::Ground(ground_variant) = variant {
-            if let Ok(children) = child_query.get_mut(*entity) {
-                let child = children[1];
-                let mut sprite = query.get_mut(child).unwrap();
-                *sprite = TextureAtlasSprite::new(ground_variant.get_sprite_sheet_index());
+            if let Ok(children) = child_query.get(*entity) {
+                for child in children.iter() {
+                    if let Ok(mut sprite) = query.get_mut(*child) {
+                        *sprite = TextureAtlasSprite::new(ground_variant.get_sprite_sheet_index());
+                    }
+                }
             }
         }
     }
@@ -1,13 +1,20 @@
+pub(crate) mod metadata;
+pub(crate) mod minimap;
+pub(crate) mod physics;
+pub(crate) mod play;
+pub(crate) mod theme;
 pub(crate) mod tiles;
+pub(crate) mod tools;
 
 use app_config::*;
 use app_core::{
-    CourseRes, GameModeToggleButton, GameModeToggleButtonImage, GameModeToggleEvent,
-    TileSpriteHandles, UiButtonSpriteHandles, UiButtonVariant,
+    now_millis, CourseRes, GameModeBuild, GameModeEdit, GameModeToggleButton,
+    GameModeToggleButtonImage, GameModeToggleEvent, SwitchAreaEvent, UiButtonSpriteHandles,
+    UiButtonVariant,
 };
 use bevy::{prelude::*, ui::FocusPolicy};
 use js_sys::{Array, Uint8Array};
-use shrm_core::Course;
+use shrm_core::{AreaId, Course};
 use wasm_bindgen::{prelude::*, JsCast};
 use web_sys::{Blob, HtmlElement, MouseEvent, Url};
 
@@ -15,12 +22,20 @@ use web_sys::{Blob, HtmlElement, MouseEvent, Url};
 
 pub struct ExportButton;
 
+#[derive(Component)]
+pub struct AreaSwitchButton;
+
+#[derive(Component)]
+pub struct AreaSwitchButtonText;
+
 pub fn setup_game_ui(
     mut commands: Commands,
-    tile_sprite_handles: Res<TileSpriteHandles>,
+    asset_server: Res<AssetServer>,
     ui_button_sprite_handles: Res<UiButtonSpriteHandles>,
+    mut images: ResMut<Assets<Image>>,
+    course: Res<CourseRes>,
 ) {
-    tiles::spawn_tile_buttons(&mut commands, &tile_sprite_handles);
+    tiles::spawn_tile_buttons(&mut commands, &asset_server);
 
     commands
         .spawn(NodeBundle {
@@ -39,9 +54,58 @@ pub fn setup_game_ui(
             ..Default::default()
         })
         .with_children(|parent| {
+            spawn_area_switch_button(parent, &asset_server);
+            theme::spawn_theme_button(parent, &asset_server, &course.theme);
+            play::spawn_play_button(parent, &asset_server);
+            play::spawn_load_replay_button(parent, &asset_server);
+            metadata::spawn_metadata_button(parent, &asset_server);
             spawn_export_button(parent, &ui_button_sprite_handles);
             spawn_game_mode_toggle_button(parent, &ui_button_sprite_handles);
         });
+    play::spawn_play_ui(&mut commands, &asset_server);
+    minimap::spawn_minimap(&mut commands, &mut images, &course);
+}
+
+fn spawn_area_switch_button(parent: &mut ChildBuilder, asset_server: &AssetServer) {
+    parent
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    size: Size::new(Val::Px(72.), Val::Px(48.)),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..Default::default()
+                },
+                background_color: NORMAL_BUTTON_COLOR.into(),
+                ..Default::default()
+            },
+            AreaSwitchButton,
+            GameModeEdit,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle {
+                    text: Text::from_section(
+                        area_label(AreaId::Main),
+                        TextStyle {
+                            font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+                            font_size: 24.,
+                            color: Color::rgb(0.9, 0.9, 0.9),
+                        },
+                    ),
+                    focus_policy: FocusPolicy::Pass,
+                    ..Default::default()
+                },
+                AreaSwitchButtonText,
+            ));
+        });
+}
+
+fn area_label(area: AreaId) -> &'static str {
+    match area {
+        AreaId::Main => "Main",
+        AreaId::Sub => "Sub",
+    }
 }
 
 fn spawn_export_button(
@@ -61,6 +125,7 @@ fn spawn_export_button(
                 ..Default::default()
             },
             ExportButton,
+            GameModeBuild,
         ))
         .with_children(|parent| {
             parent.spawn(ImageBundle {
@@ -102,6 +167,7 @@ fn spawn_game_mode_toggle_button(
                 ..Default::default()
             },
             GameModeToggleButton { is_editing: true },
+            GameModeBuild,
         ))
         .with_children(|parent| {
             parent.spawn((
@@ -131,50 +197,55 @@ fn spawn_game_mode_toggle_button(
 
 pub fn export(
     mut query: Query<&Interaction, (With<ExportButton>, Changed<Interaction>)>,
-    course: Res<CourseRes>,
+    mut course: ResMut<CourseRes>,
 ) {
     for interaction in query.iter_mut() {
         if *interaction == Interaction::Clicked {
+            course.metadata.modified_at = now_millis();
             let course: Course = course.as_ref().into();
-            let data = course.serialize().unwrap();
-            let js_data = unsafe { Uint8Array::view(&data[..]) };
-
-            let window = web_sys::window().unwrap();
-            let document = window.document().unwrap();
-            let anchor = document.create_element("a").unwrap();
-            let blob = Blob::new_with_u8_array_sequence(&Array::of1(&js_data)).unwrap();
-            let obj_url = Url::create_object_url_with_blob(&blob).unwrap();
-            anchor.set_attribute("href", &obj_url).unwrap();
-            anchor.set_attribute("download", "course.ron.br").unwrap();
-            anchor
-                .set_attribute("style", "{\"display\": \"none\"}")
-                .unwrap();
-
-            // TODO enable weakrefs to prevent memory leak
-            // https://rustwasm.github.io/wasm-bindgen/api/wasm_bindgen/closure/struct.Closure.html#method.into_js_value
-            let click_closure = Closure::<dyn FnMut(MouseEvent)>::new(|event: MouseEvent| {
-                event.stop_propagation();
-            });
-            anchor
-                .add_event_listener_with_callback("click", click_closure.as_ref().unchecked_ref())
-                .unwrap();
-            click_closure.forget();
-            document.body().unwrap().append_child(&anchor).unwrap();
-            anchor.unchecked_ref::<HtmlElement>().click();
-
-            let closure = Closure::<dyn FnMut()>::new(move || {
-                let body = document.body().unwrap();
-                body.remove_child(&anchor).unwrap();
-                Url::revoke_object_url(&anchor.get_attribute("href").unwrap()).unwrap();
-            });
-            window
-                .set_timeout_with_callback(closure.as_ref().unchecked_ref())
-                .unwrap();
-            closure.forget();
+            download(&course.serialize().unwrap(), &course.metadata.file_name());
         }
     }
 }
 
+/// Lets the browser save `data` as a file.
+pub(crate) fn download(data: &[u8], file_name: &str) {
+    let js_data = unsafe { Uint8Array::view(data) };
+
+    let window = web_sys::window().unwrap();
+    let document = window.document().unwrap();
+    let anchor = document.create_element("a").unwrap();
+    let blob = Blob::new_with_u8_array_sequence(&Array::of1(&js_data)).unwrap();
+    let obj_url = Url::create_object_url_with_blob(&blob).unwrap();
+    anchor.set_attribute("href", &obj_url).unwrap();
+    anchor.set_attribute("download", file_name).unwrap();
+    anchor
+        .set_attribute("style", "{\"display\": \"none\"}")
+        .unwrap();
+
+    // TODO enable weakrefs to prevent memory leak
+    // https://rustwasm.github.io/wasm-bindgen/api/wasm_bindgen/closure/struct.Closure.html#method.into_js_value
+    let click_closure = Closure::<dyn FnMut(MouseEvent)>::new(|event: MouseEvent| {
+        event.stop_propagation();
+    });
+    anchor
+        .add_event_listener_with_callback("click", click_closure.as_ref().unchecked_ref())
+        .unwrap();
+    click_closure.forget();
+    document.body().unwrap().append_child(&anchor).unwrap();
+    anchor.unchecked_ref::<HtmlElement>().click();
+
+    let closure = Closure::<dyn FnMut()>::new(move || {
+        let body = document.body().unwrap();
+        body.remove_child(&anchor).unwrap();
+        Url::revoke_object_url(&anchor.get_attribute("href").unwrap()).unwrap();
+    });
+    window
+        .set_timeout_with_callback(closure.as_ref().unchecked_ref())
+        .unwrap();
+    closure.forget();
+}
+
 pub fn toggle_game_mode(
     query: Query<(&Interaction, &GameModeToggleButton), Changed<Interaction>>,
     mut game_mode_toggle_event: EventWriter<GameModeToggleEvent>,
@@ -186,3 +257,28 @@ pub fn toggle_game_mode(
         }
     }
 }
+
+pub fn switch_area(
+    query: Query<&Interaction, (With<AreaSwitchButton>, Changed<Interaction>)>,
+    mut text_query: Query<&mut Text, With<AreaSwitchButtonText>>,
+    course: Res<CourseRes>,
+    mut switch_area_events: EventWriter<SwitchAreaEvent>,
+) {
+    for interaction in query.iter() {
+        if *interaction == Interaction::Clicked {
+            let area = match course.area {
+                AreaId::Main => AreaId::Sub,
+                AreaId::Sub => AreaId::Main,
+            };
+            switch_area_events.send(SwitchAreaEvent(area));
+        }
+    }
+    // the area also changes when play mode reloads the course
+    if course.is_changed() {
+        if let Ok(mut text) = text_query.get_single_mut() {
+            if text.sections[0].value != area_label(course.area) {
+                text.sections[0].value = area_label(course.area).to_string();
+            }
+        }
+    }
+}
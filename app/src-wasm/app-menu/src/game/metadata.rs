@@ -0,0 +1,465 @@
+use app_config::*;
+use app_core::{now_millis, CourseRes, GameModeBuild, GroundTileUpdateEvent, TextInputFocus};
+use bevy::{prelude::*, ui::FocusPolicy};
+use js_sys::Date;
+use shrm_core::{
+    AreaId, CourseMetadata, MAX_COURSE_CELLS, MAX_COURSE_DESCRIPTION_LEN, MAX_COURSE_TAGS,
+    MAX_COURSE_TAG_LEN, MAX_COURSE_TITLE_LEN, MAX_COURSE_X, MAX_COURSE_Y, MIN_COURSE_X,
+    MIN_COURSE_Y,
+};
+use std::collections::HashMap;
+use wasm_bindgen::JsValue;
+
+const FONT: &str = "fonts/FiraSans-Bold.ttf";
+const LABEL_FONT_SIZE: f32 = 16.;
+const FIELD_FONT_SIZE: f32 = 20.;
+const PANEL_WIDTH: f32 = 360.;
+
+#[derive(Component)]
+pub struct MetadataButton;
+
+#[derive(Component)]
+pub struct MetadataPanel;
+
+#[derive(Clone, Component, Copy, Debug, Eq, PartialEq)]
+pub enum MetadataField {
+    Title,
+    Description,
+    Tags,
+    /// Width of the loaded area.
+    Width,
+    /// Height of the loaded area.
+    Height,
+}
+
+#[derive(Component)]
+pub struct MetadataFieldText(MetadataField);
+
+#[derive(Component)]
+pub struct MetadataInfoText;
+
+#[derive(Default, Resource)]
+pub struct MetadataEditor {
+    focused: Option<MetadataField>,
+    /// Tags as typed, so that separators can be entered before the next tag.
+    tags: String,
+    /// Width or height as typed, applied once the field loses focus.
+    size: String,
+}
+
+impl MetadataEditor {
+    /// Whether a field is focused, which takes all keyboard input.
+    pub(crate) fn is_focused(&self) -> bool {
+        self.focused.is_some()
+    }
+}
+
+/// Mirrors the focus for the shortcuts of other plugins, which run before `edit_metadata`.
+pub fn update_text_input_focus(
+    editor: Res<MetadataEditor>,
+    mut text_input_focus: ResMut<TextInputFocus>,
+) {
+    if text_input_focus.0 != editor.is_focused() {
+        text_input_focus.0 = editor.is_focused();
+    }
+}
+
+pub(crate) fn spawn_metadata_button(parent: &mut ChildBuilder, asset_server: &AssetServer) {
+    parent
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    size: Size::new(Val::Px(48.), Val::Px(48.)),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..Default::default()
+                },
+                background_color: NORMAL_BUTTON_COLOR.into(),
+                ..Default::default()
+            },
+            MetadataButton,
+            GameModeBuild,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle {
+                text: Text::from_section(
+                    "i",
+                    TextStyle {
+                        font: asset_server.load(FONT),
+                        font_size: 36.,
+                        color: Color::rgb(0.9, 0.9, 0.9),
+                    },
+                ),
+                focus_policy: FocusPolicy::Pass,
+                ..Default::default()
+            });
+        });
+}
+
+pub fn toggle_metadata_panel(
+    mut commands: Commands,
+    button_query: Query<&Interaction, (With<MetadataButton>, Changed<Interaction>)>,
+    panel_query: Query<Entity, With<MetadataPanel>>,
+    course: Res<CourseRes>,
+    asset_server: Res<AssetServer>,
+    mut editor: ResMut<MetadataEditor>,
+) {
+    if let Some(&Interaction::Clicked) = button_query.iter().next() {
+        editor.focused = None;
+        if let Some(panel) = panel_query.iter().next() {
+            commands.entity(panel).despawn_recursive();
+        } else {
+            editor.tags = course.metadata.tags.join(", ");
+            spawn_metadata_panel(&mut commands, &asset_server, &course, &editor);
+        }
+    }
+}
+
+fn spawn_metadata_panel(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    course: &CourseRes,
+    editor: &MetadataEditor,
+) {
+    let font = asset_server.load(FONT);
+    let label_style = TextStyle {
+        font: font.clone(),
+        font_size: LABEL_FONT_SIZE,
+        color: Color::rgb(0.9, 0.9, 0.9),
+    };
+    let field_style = TextStyle {
+        font,
+        font_size: FIELD_FONT_SIZE,
+        color: Color::rgb(0.9, 0.9, 0.9),
+    };
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    position: UiRect {
+                        top: Val::Px(66.),
+                        right: Val::Px(12.),
+                        ..Default::default()
+                    },
+                    size: Size::new(Val::Px(PANEL_WIDTH), Val::Auto),
+                    flex_direction: FlexDirection::ColumnReverse,
+                    padding: UiRect::all(Val::Px(8.)),
+                    ..Default::default()
+                },
+                background_color: PANEL_COLOR.into(),
+                ..Default::default()
+            },
+            // blocks tile placement below the panel
+            Interaction::default(),
+            MetadataPanel,
+            GameModeBuild,
+        ))
+        .with_children(|parent| {
+            for (label, field) in [
+                ("Title", MetadataField::Title),
+                ("Description", MetadataField::Description),
+                ("Tags (comma separated)", MetadataField::Tags),
+                ("Area width", MetadataField::Width),
+                ("Area height", MetadataField::Height),
+            ] {
+                parent.spawn(TextBundle::from_section(label, label_style.clone()));
+                parent
+                    .spawn((
+                        ButtonBundle {
+                            style: Style {
+                                min_size: Size::new(Val::Auto, Val::Px(FIELD_FONT_SIZE + 8.)),
+                                margin: UiRect::new(
+                                    Val::Px(0.),
+                                    Val::Px(0.),
+                                    Val::Px(2.),
+                                    Val::Px(8.),
+                                ),
+                                padding: UiRect::all(Val::Px(4.)),
+                                ..Default::default()
+                            },
+                            background_color: NORMAL_BUTTON_COLOR.into(),
+                            ..Default::default()
+                        },
+                        field,
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn((
+                            TextBundle {
+                                text: Text::from_section(
+                                    field_text(field, course, editor),
+                                    field_style.clone(),
+                                ),
+                                style: Style {
+                                    max_size: Size::new(Val::Px(PANEL_WIDTH - 24.), Val::Undefined),
+                                    ..Default::default()
+                                },
+                                focus_policy: FocusPolicy::Pass,
+                                ..Default::default()
+                            },
+                            MetadataFieldText(field),
+                        ));
+                    });
+            }
+            parent.spawn((
+                TextBundle::from_section(info_text(&course.metadata), label_style.clone()),
+                MetadataInfoText,
+            ));
+        });
+}
+
+pub fn focus_metadata_field(
+    mut commands: Commands,
+    mouse_button_input: Res<Input<MouseButton>>,
+    query: Query<(&Interaction, &MetadataField)>,
+    mut course: ResMut<CourseRes>,
+    mut editor: ResMut<MetadataEditor>,
+    mut ground_tile_update_events: EventWriter<GroundTileUpdateEvent>,
+) {
+    if !mouse_button_input.just_pressed(MouseButton::Left) {
+        return;
+    }
+    let focused = query
+        .iter()
+        .find(|(interaction, _)| **interaction == Interaction::Clicked)
+        .map(|(_, field)| *field);
+    if editor.focused != focused {
+        focus(
+            focused,
+            &mut commands,
+            &mut course,
+            &mut editor,
+            &mut ground_tile_update_events,
+        );
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn edit_metadata(
+    mut commands: Commands,
+    mut char_events: EventReader<ReceivedCharacter>,
+    mut keyboard_input: ResMut<Input<KeyCode>>,
+    mut text_query: Query<(&mut Text, &MetadataFieldText)>,
+    mut info_query: Query<&mut Text, (With<MetadataInfoText>, Without<MetadataFieldText>)>,
+    mut course: ResMut<CourseRes>,
+    mut editor: ResMut<MetadataEditor>,
+    mut ground_tile_update_events: EventWriter<GroundTileUpdateEvent>,
+) {
+    let field = if let Some(field) = editor.focused {
+        field
+    } else {
+        char_events.clear();
+        // the loaded area might have been switched
+        update_texts(&mut text_query, &course, &editor);
+        return;
+    };
+
+    let modified_at = course.metadata.modified_at;
+    if keyboard_input.just_pressed(KeyCode::Return) {
+        focus(
+            None,
+            &mut commands,
+            &mut course,
+            &mut editor,
+            &mut ground_tile_update_events,
+        );
+    } else if keyboard_input.just_pressed(KeyCode::Escape) {
+        editor.focused = None;
+    }
+
+    let mut changed = course.metadata.modified_at != modified_at;
+    if keyboard_input.just_pressed(KeyCode::Back) {
+        changed |= edit_field(field, &mut course.metadata, &mut editor, |text| {
+            text.pop();
+        });
+    }
+    for ReceivedCharacter { char, .. } in char_events.iter() {
+        if char.is_control() {
+            continue;
+        }
+        changed |= edit_field(field, &mut course.metadata, &mut editor, |text| {
+            text.push(*char)
+        });
+    }
+    // typing must not move the player
+    keyboard_input.reset_all();
+
+    if changed {
+        course.metadata.modified_at = now_millis();
+        if let Ok(mut text) = info_query.get_single_mut() {
+            text.sections[0].value = info_text(&course.metadata);
+        }
+    }
+    update_texts(&mut text_query, &course, &editor);
+}
+
+/// Moves the focus to `field`, applying the size typed into the previously focused field.
+fn focus(
+    field: Option<MetadataField>,
+    commands: &mut Commands,
+    course: &mut CourseRes,
+    editor: &mut MetadataEditor,
+    ground_tile_update_events: &mut EventWriter<GroundTileUpdateEvent>,
+) {
+    if let Some(prev_field @ (MetadataField::Width | MetadataField::Height)) = editor.focused {
+        if let Ok(size) = editor.size.parse() {
+            resize_area(
+                prev_field,
+                size,
+                commands,
+                course,
+                ground_tile_update_events,
+            );
+        }
+    }
+    editor.focused = field;
+    match field {
+        Some(MetadataField::Width) => editor.size = course.width.to_string(),
+        Some(MetadataField::Height) => editor.size = course.height.to_string(),
+        _ => {}
+    }
+}
+
+/// Resizes the loaded area, clamping `size` to the course limits.
+///
+/// The main area can not get narrower than its goal or lower than its start.
+fn resize_area(
+    field: MetadataField,
+    size: i32,
+    commands: &mut Commands,
+    course: &mut CourseRes,
+    ground_tile_update_events: &mut EventWriter<GroundTileUpdateEvent>,
+) {
+    let (mut width, mut height) = (course.width, course.height);
+    match field {
+        MetadataField::Width => {
+            width = size.clamp(MIN_COURSE_X, MAX_COURSE_X.min(MAX_COURSE_CELLS / height));
+            if course.area == AreaId::Main {
+                width = width.max(course.goal_pos_x + MAX_COURSE_GOAL_OFFSET_X);
+            }
+        }
+        MetadataField::Height => {
+            height = size.clamp(MIN_COURSE_Y, MAX_COURSE_Y.min(MAX_COURSE_CELLS / width));
+            if course.area == AreaId::Main {
+                height = height.max(course.player_start()[1] + 1);
+            }
+        }
+        _ => return,
+    }
+    if width == course.width && height == course.height {
+        return;
+    }
+
+    let mut events = HashMap::new();
+    course.resize(commands, width, height, &mut events);
+    for event in events.into_values() {
+        ground_tile_update_events.send(event);
+    }
+    course.metadata.modified_at = now_millis();
+}
+
+/// Applies `edit` to the text of `field`, unless the result would exceed the course limits.
+fn edit_field(
+    field: MetadataField,
+    metadata: &mut CourseMetadata,
+    editor: &mut MetadataEditor,
+    edit: impl FnOnce(&mut String),
+) -> bool {
+    match field {
+        MetadataField::Title => edit_text(&mut metadata.title, MAX_COURSE_TITLE_LEN, edit),
+        MetadataField::Description => {
+            edit_text(&mut metadata.description, MAX_COURSE_DESCRIPTION_LEN, edit)
+        }
+        MetadataField::Tags => {
+            let mut tags = editor.tags.clone();
+            edit(&mut tags);
+            let parsed = parse_tags(&tags);
+            if tags == editor.tags
+                || parsed.len() > MAX_COURSE_TAGS
+                || parsed
+                    .iter()
+                    .any(|tag| tag.chars().count() > MAX_COURSE_TAG_LEN)
+            {
+                return false;
+            }
+            editor.tags = tags;
+            metadata.tags = parsed;
+            true
+        }
+        MetadataField::Width | MetadataField::Height => {
+            let mut size = editor.size.clone();
+            edit(&mut size);
+            if size.len() <= 3 && size.chars().all(|char| char.is_ascii_digit()) {
+                editor.size = size;
+            }
+            // the size is only applied once the field loses focus
+            false
+        }
+    }
+}
+
+fn edit_text(text: &mut String, max_len: usize, edit: impl FnOnce(&mut String)) -> bool {
+    let mut edited = text.clone();
+    edit(&mut edited);
+    if edited == *text || edited.chars().count() > max_len {
+        return false;
+    }
+    *text = edited;
+    true
+}
+
+fn parse_tags(tags: &str) -> Vec<String> {
+    tags.split(',')
+        .map(str::trim)
+        .filter(|tag| !tag.is_empty())
+        .map(ToString::to_string)
+        .collect()
+}
+
+fn update_texts(
+    text_query: &mut Query<(&mut Text, &MetadataFieldText)>,
+    course: &CourseRes,
+    editor: &MetadataEditor,
+) {
+    for (mut text, MetadataFieldText(field)) in text_query.iter_mut() {
+        let value = field_text(*field, course, editor);
+        if text.sections[0].value != value {
+            text.sections[0].value = value;
+        }
+    }
+}
+
+fn field_text(field: MetadataField, course: &CourseRes, editor: &MetadataEditor) -> String {
+    let focused = editor.focused == Some(field);
+    let mut text = match field {
+        MetadataField::Title => course.metadata.title.clone(),
+        MetadataField::Description => course.metadata.description.clone(),
+        MetadataField::Tags => editor.tags.clone(),
+        MetadataField::Width | MetadataField::Height if focused => editor.size.clone(),
+        MetadataField::Width => course.width.to_string(),
+        MetadataField::Height => course.height.to_string(),
+    };
+    if focused {
+        text.push('|');
+    }
+    text
+}
+
+fn info_text(metadata: &CourseMetadata) -> String {
+    format!(
+        "Author: {}\nCreated: {}\nModified: {}",
+        metadata.author_id.as_deref().unwrap_or("-"),
+        format_time(metadata.created_at),
+        format_time(metadata.modified_at),
+    )
+}
+
+fn format_time(millis: u64) -> String {
+    if millis == 0 {
+        return "-".to_string();
+    }
+    Date::new(&JsValue::from_f64(millis as f64))
+        .to_locale_string("default", &JsValue::UNDEFINED)
+        .into()
+}
@@ -0,0 +1,276 @@
+use app_config::*;
+use app_core::{
+    pos_to_world, CourseRes, DespawnTileEvent, GameMode, GameModeBuild, MoveCameraEvent,
+    SpawnTileEvent,
+};
+use app_player::Player;
+use bevy::{
+    prelude::*,
+    render::{
+        camera::Camera,
+        primitives::Frustum,
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+    },
+    ui::FocusPolicy,
+};
+use shrm_core::{AreaId, TileVariant};
+
+/// Size of a tile on the minimap in pixels.
+const MINIMAP_TILE_SIZE: f32 = 2.;
+
+/// Image of the loaded area with one pixel per tile.
+#[derive(Component)]
+pub struct Minimap(Handle<Image>);
+
+#[derive(Component, Clone, Copy)]
+pub enum MinimapMarker {
+    Viewport,
+    Goal,
+    Player,
+}
+
+/// Whatever requires the minimap to be drawn from scratch when it changes.
+#[derive(PartialEq)]
+pub(crate) struct MinimapLayout {
+    area: AreaId,
+    width: i32,
+    height: i32,
+    goal_pos_x: i32,
+    start_pos: [i32; 2],
+}
+
+impl From<&CourseRes> for MinimapLayout {
+    fn from(course: &CourseRes) -> Self {
+        Self {
+            area: course.area,
+            width: course.right_edge(),
+            height: course.height,
+            goal_pos_x: course.goal_pos_x,
+            start_pos: course.start_pos,
+        }
+    }
+}
+
+pub(crate) fn spawn_minimap(
+    commands: &mut Commands,
+    images: &mut Assets<Image>,
+    course: &CourseRes,
+) {
+    let handle = images.add(draw_minimap(course));
+    commands
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    position: UiRect {
+                        bottom: Val::Px(12.),
+                        left: Val::Px(12.),
+                        ..Default::default()
+                    },
+                    size: minimap_size(course),
+                    overflow: Overflow::Hidden,
+                    ..Default::default()
+                },
+                image: UiImage(handle.clone()),
+                ..Default::default()
+            },
+            Minimap(handle),
+            GameModeBuild,
+        ))
+        .with_children(|parent| {
+            let marker_size = Size::new(Val::Px(MINIMAP_TILE_SIZE), Val::Px(MINIMAP_TILE_SIZE));
+            for (marker, color) in [
+                (MinimapMarker::Viewport, MINIMAP_VIEWPORT_COLOR),
+                (MinimapMarker::Goal, MINIMAP_GOAL_COLOR),
+                (MinimapMarker::Player, MINIMAP_PLAYER_COLOR),
+            ] {
+                parent.spawn((
+                    NodeBundle {
+                        style: Style {
+                            position_type: PositionType::Absolute,
+                            size: marker_size,
+                            ..Default::default()
+                        },
+                        background_color: color.into(),
+                        focus_policy: FocusPolicy::Pass,
+                        ..Default::default()
+                    },
+                    marker,
+                ));
+            }
+        });
+}
+
+fn minimap_size(course: &CourseRes) -> Size {
+    Size::new(
+        Val::Px(course.right_edge() as f32 * MINIMAP_TILE_SIZE),
+        Val::Px(course.height as f32 * MINIMAP_TILE_SIZE),
+    )
+}
+
+fn draw_minimap(course: &CourseRes) -> Image {
+    let mut image = Image::new_fill(
+        Extent3d {
+            width: course.right_edge().max(1) as u32,
+            height: course.height.max(1) as u32,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &color_bytes(MINIMAP_EMPTY_COLOR),
+        TextureFormat::Rgba8UnormSrgb,
+    );
+    for (grid_pos, tile) in course.tiles.iter() {
+        draw_tile(&mut image, *grid_pos, Some(&tile.variant));
+    }
+    image
+}
+
+fn draw_tile(image: &mut Image, [x, y]: [i32; 2], tile_variant: Option<&TileVariant>) {
+    let size = image.texture_descriptor.size;
+    if x < 0 || y < 0 || x as u32 >= size.width || y as u32 >= size.height {
+        return;
+    }
+    let color = match tile_variant {
+        Some(TileVariant::Ground(_)) => MINIMAP_GROUND_COLOR,
+        Some(_) => MINIMAP_BLOCK_COLOR,
+        None => MINIMAP_EMPTY_COLOR,
+    };
+    // the rows of the image go from top to bottom
+    let index = ((size.height - 1 - y as u32) * size.width + x as u32) as usize * 4;
+    image.data[index..index + 4].copy_from_slice(&color_bytes(color));
+}
+
+fn color_bytes(color: Color) -> [u8; 4] {
+    color
+        .as_rgba_f32()
+        .map(|channel| (channel * 255.).round() as u8)
+}
+
+/// Redraws the tiles of the spawn and despawn events,
+/// or the whole minimap if the course changed in any other way.
+pub(crate) fn update_minimap(
+    mut query: Query<(&Minimap, &mut Style)>,
+    mut images: ResMut<Assets<Image>>,
+    mut spawn_tile_events: EventReader<SpawnTileEvent>,
+    mut despawn_tile_events: EventReader<DespawnTileEvent>,
+    course: Res<CourseRes>,
+    mut layout: Local<Option<MinimapLayout>>,
+) {
+    let grid_positions: Vec<_> = spawn_tile_events
+        .iter()
+        .map(|event| event.grid_pos)
+        .chain(despawn_tile_events.iter().map(|event| event.grid_pos))
+        .collect();
+    let (minimap, mut style) = if let Ok(minimap) = query.get_single_mut() {
+        minimap
+    } else {
+        return;
+    };
+    let image = if let Some(image) = images.get_mut(&minimap.0) {
+        image
+    } else {
+        return;
+    };
+
+    let next_layout = Some(MinimapLayout::from(course.as_ref()));
+    // goal and start moves as well as undo and redo change the tiles directly
+    if *layout != next_layout || (course.is_changed() && grid_positions.is_empty()) {
+        *layout = next_layout;
+        *image = draw_minimap(&course);
+        let size = minimap_size(&course);
+        if style.size != size {
+            style.size = size;
+        }
+        return;
+    }
+    for grid_pos in grid_positions {
+        let tile_variant = course.tiles.get(&grid_pos).map(|tile| &tile.variant);
+        draw_tile(image, grid_pos, tile_variant);
+    }
+}
+
+pub fn update_minimap_markers(
+    mut query: Query<(&mut Style, &MinimapMarker)>,
+    camera_query: Query<&Transform, (With<Camera>, With<Frustum>)>,
+    player_query: Query<&Transform, With<Player>>,
+    windows: Res<Windows>,
+    course: Res<CourseRes>,
+) {
+    let (camera_transform, player_transform) =
+        if let (Ok(camera), Ok(player)) = (camera_query.get_single(), player_query.get_single()) {
+            (camera, player)
+        } else {
+            return;
+        };
+    let window = windows.get_primary().unwrap();
+    let [width, height] = [course.right_edge() as f32, course.height as f32];
+    // tiles are centered on their grid position
+    let to_minimap = |pos: Vec2| pos / pos_to_world(1) + 0.5;
+
+    for (mut style, marker) in query.iter_mut() {
+        let (pos, size) = match marker {
+            MinimapMarker::Viewport => {
+                let half_size = Vec2::new(window.width(), window.height()) / 2.
+                    * camera_transform.scale.truncate();
+                let center = camera_transform.translation.truncate();
+                let min = to_minimap(center - half_size);
+                (min, to_minimap(center + half_size) - min)
+            }
+            MinimapMarker::Goal => (
+                Vec2::new(course.goal_pos_x as f32 + 1., 0.),
+                Vec2::new(1., height),
+            ),
+            MinimapMarker::Player => (
+                to_minimap(player_transform.translation.truncate()) - 1.,
+                Vec2::splat(2.),
+            ),
+        };
+        let position = UiRect {
+            left: Val::Percent(pos.x / width * 100.),
+            bottom: Val::Percent(pos.y / height * 100.),
+            ..Default::default()
+        };
+        let size = Size::new(
+            Val::Percent(size.x / width * 100.),
+            Val::Percent(size.y / height * 100.),
+        );
+        let display = if matches!(marker, MinimapMarker::Goal) && course.area != AreaId::Main {
+            Display::None
+        } else {
+            Display::Flex
+        };
+        if style.position != position || style.size != size || style.display != display {
+            style.position = position;
+            style.size = size;
+            style.display = display;
+        }
+    }
+}
+
+/// Moves the camera to the clicked position while editing.
+pub fn click_minimap(
+    query: Query<(&Interaction, &Node, &GlobalTransform), With<Minimap>>,
+    windows: Res<Windows>,
+    course: Res<CourseRes>,
+    game_mode: Res<GameMode>,
+    mut move_camera_events: EventWriter<MoveCameraEvent>,
+) {
+    if !matches!(*game_mode, GameMode::Build { is_editing: true }) {
+        return;
+    }
+    let (interaction, node, transform) = if let Ok(minimap) = query.get_single() {
+        minimap
+    } else {
+        return;
+    };
+    if *interaction != Interaction::Clicked {
+        return;
+    }
+    let window = windows.get_primary().unwrap();
+    if let Some(cursor_position) = window.cursor_position() {
+        let min = transform.translation().truncate() - node.size() / 2.;
+        let fraction = (cursor_position - min) / node.size();
+        let grid_pos = fraction * Vec2::new(course.right_edge() as f32, course.height as f32);
+        move_camera_events.send(MoveCameraEvent((grid_pos - 0.5) * pos_to_world(1)));
+    }
+}
@@ -0,0 +1,209 @@
+use super::metadata::MetadataEditor;
+use app_config::*;
+use app_player::{PhysicsProfile, PHYSICS_PROFILE_PATH};
+use bevy::{prelude::*, ui::FocusPolicy};
+
+const FONT: &str = "fonts/FiraSans-Bold.ttf";
+const FONT_SIZE: f32 = 16.;
+const TOGGLE_KEY: KeyCode = KeyCode::F3;
+
+/// Tunes the [`PhysicsProfile`] while playing.
+#[derive(Component)]
+pub struct PhysicsPanel;
+
+#[derive(Component)]
+pub struct PhysicsStepButton {
+    field: usize,
+    steps: i8,
+}
+
+#[derive(Component)]
+pub struct PhysicsValueText(usize);
+
+#[derive(Component)]
+pub struct PhysicsResetButton;
+
+#[derive(Component)]
+pub struct PhysicsSaveButton;
+
+pub fn toggle_physics_panel(
+    mut commands: Commands,
+    panel_query: Query<Entity, With<PhysicsPanel>>,
+    keyboard_input: Res<Input<KeyCode>>,
+    editor: Res<MetadataEditor>,
+    asset_server: Res<AssetServer>,
+    profile: Res<PhysicsProfile>,
+) {
+    if editor.is_focused() || !keyboard_input.just_pressed(TOGGLE_KEY) {
+        return;
+    }
+    if let Some(panel) = panel_query.iter().next() {
+        commands.entity(panel).despawn_recursive();
+    } else {
+        spawn_physics_panel(&mut commands, &asset_server, &profile);
+    }
+}
+
+fn spawn_physics_panel(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    profile: &PhysicsProfile,
+) {
+    let text_style = TextStyle {
+        font: asset_server.load(FONT),
+        font_size: FONT_SIZE,
+        color: Color::rgb(0.9, 0.9, 0.9),
+    };
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    position: UiRect {
+                        top: Val::Px(66.),
+                        left: Val::Px(12.),
+                        ..Default::default()
+                    },
+                    flex_direction: FlexDirection::ColumnReverse,
+                    padding: UiRect::all(Val::Px(8.)),
+                    ..Default::default()
+                },
+                background_color: PANEL_COLOR.into(),
+                ..Default::default()
+            },
+            // blocks tile placement below the panel
+            Interaction::default(),
+            PhysicsPanel,
+        ))
+        .with_children(|parent| {
+            let mut profile = profile.clone();
+            for (field, (name, value)) in profile.fields_mut().into_iter().enumerate() {
+                parent
+                    .spawn(NodeBundle {
+                        style: Style {
+                            align_items: AlignItems::Center,
+                            margin: UiRect::bottom(Val::Px(2.)),
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    })
+                    .with_children(|parent| {
+                        parent.spawn(TextBundle {
+                            style: Style {
+                                size: Size::new(Val::Px(200.), Val::Auto),
+                                ..Default::default()
+                            },
+                            text: Text::from_section(name, text_style.clone()),
+                            ..Default::default()
+                        });
+                        spawn_button(
+                            parent,
+                            "-",
+                            &text_style,
+                            PhysicsStepButton { field, steps: -1 },
+                        );
+                        parent.spawn((
+                            TextBundle {
+                                style: Style {
+                                    size: Size::new(Val::Px(80.), Val::Auto),
+                                    ..Default::default()
+                                },
+                                text: Text::from_section(value.label(), text_style.clone())
+                                    .with_alignment(TextAlignment::CENTER),
+                                ..Default::default()
+                            },
+                            PhysicsValueText(field),
+                        ));
+                        spawn_button(
+                            parent,
+                            "+",
+                            &text_style,
+                            PhysicsStepButton { field, steps: 1 },
+                        );
+                    });
+            }
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        justify_content: JustifyContent::FlexEnd,
+                        margin: UiRect::top(Val::Px(6.)),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                })
+                .with_children(|parent| {
+                    spawn_button(parent, "Reset", &text_style, PhysicsResetButton);
+                    spawn_button(parent, "Save", &text_style, PhysicsSaveButton);
+                });
+        });
+}
+
+fn spawn_button(
+    parent: &mut ChildBuilder,
+    label: &str,
+    text_style: &TextStyle,
+    components: impl Bundle,
+) {
+    parent
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    min_size: Size::new(Val::Px(24.), Val::Px(24.)),
+                    margin: UiRect::horizontal(Val::Px(2.)),
+                    padding: UiRect::horizontal(Val::Px(6.)),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..Default::default()
+                },
+                background_color: NORMAL_BUTTON_COLOR.into(),
+                ..Default::default()
+            },
+            components,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle {
+                text: Text::from_section(label, text_style.clone()),
+                focus_policy: FocusPolicy::Pass,
+                ..Default::default()
+            });
+        });
+}
+
+#[allow(clippy::type_complexity)]
+pub fn physics_panel_buttons(
+    step_query: Query<(&Interaction, &PhysicsStepButton), Changed<Interaction>>,
+    reset_query: Query<&Interaction, (With<PhysicsResetButton>, Changed<Interaction>)>,
+    save_query: Query<&Interaction, (With<PhysicsSaveButton>, Changed<Interaction>)>,
+    mut profile: ResMut<PhysicsProfile>,
+) {
+    for (interaction, PhysicsStepButton { field, steps }) in step_query.iter() {
+        if *interaction == Interaction::Clicked {
+            profile.fields_mut()[*field].1.step(*steps);
+        }
+    }
+    if let Some(&Interaction::Clicked) = reset_query.iter().next() {
+        *profile = PhysicsProfile::default();
+    }
+    if let Some(&Interaction::Clicked) = save_query.iter().next() {
+        match profile.to_ron() {
+            Ok(data) => crate::game::download(data.as_bytes(), PHYSICS_PROFILE_PATH),
+            Err(err) => error!("Physics profile could not be saved: {err}"),
+        }
+    }
+}
+
+/// Shows the values of the profile, which also changes when its file is loaded.
+pub fn update_physics_panel(
+    mut text_query: Query<(&mut Text, &PhysicsValueText)>,
+    profile: Res<PhysicsProfile>,
+) {
+    if !profile.is_changed() {
+        return;
+    }
+    let mut profile = profile.clone();
+    let fields = profile.fields_mut();
+    for (mut text, PhysicsValueText(field)) in text_query.iter_mut() {
+        text.sections[0].value = fields[*field].1.label();
+    }
+}
@@ -0,0 +1,298 @@
+use app_config::*;
+use app_core::{GameModeBuild, InputReplay, PlayModeEvent, PlaySession, PlayState};
+use bevy::{prelude::*, ui::FocusPolicy};
+use shrm_core::Replay;
+use std::sync::{Arc, RwLock};
+
+const FONT: &str = "fonts/FiraSans-Bold.ttf";
+
+#[derive(Component)]
+pub struct PlayButton;
+
+#[derive(Component)]
+pub struct ExitPlayButton;
+
+#[derive(Component)]
+pub struct RestartButton;
+
+#[derive(Component)]
+pub struct SaveReplayButton;
+
+#[derive(Component)]
+pub struct LoadReplayButton;
+
+/// A replay file that was picked, but not yet started.
+#[derive(Default, Resource)]
+pub struct ReplayLoading(Arc<RwLock<Option<Result<Replay, String>>>>);
+
+/// Shown for the whole play session.
+#[derive(Component)]
+pub struct PlayHud;
+
+#[derive(Component)]
+pub struct LivesText;
+
+/// Shown once the session ended, either by a game over or by clearing the course.
+#[derive(Component)]
+pub struct PlayResultScreen;
+
+#[derive(Component)]
+pub struct PlayResultText;
+
+pub(crate) fn spawn_play_button(parent: &mut ChildBuilder, asset_server: &AssetServer) {
+    spawn_text_button(parent, asset_server, "Play", (PlayButton, GameModeBuild));
+}
+
+pub(crate) fn spawn_load_replay_button(parent: &mut ChildBuilder, asset_server: &AssetServer) {
+    spawn_text_button(
+        parent,
+        asset_server,
+        "Replay",
+        (LoadReplayButton, GameModeBuild),
+    );
+}
+
+pub(crate) fn spawn_play_ui(commands: &mut Commands, asset_server: &AssetServer) {
+    let text_style = TextStyle {
+        font: asset_server.load(FONT),
+        font_size: 24.,
+        color: Color::rgb(0.9, 0.9, 0.9),
+    };
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    display: Display::None,
+                    position_type: PositionType::Absolute,
+                    position: UiRect {
+                        top: Val::Px(6.),
+                        right: Val::Px(6.),
+                        ..Default::default()
+                    },
+                    padding: UiRect::all(Val::Px(6.)),
+                    align_items: AlignItems::Center,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            PlayHud,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle {
+                    text: Text::from_section("", text_style.clone()),
+                    style: Style {
+                        margin: UiRect::horizontal(Val::Px(12.)),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                LivesText,
+            ));
+            spawn_text_button(parent, asset_server, "Save replay", SaveReplayButton);
+            spawn_text_button(parent, asset_server, "Exit", ExitPlayButton);
+        });
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    display: Display::None,
+                    position_type: PositionType::Absolute,
+                    position: UiRect::all(Val::Px(0.)),
+                    flex_direction: FlexDirection::ColumnReverse,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..Default::default()
+                },
+                background_color: PANEL_COLOR.into(),
+                ..Default::default()
+            },
+            PlayResultScreen,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 48.,
+                        ..text_style
+                    },
+                ),
+                PlayResultText,
+            ));
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        margin: UiRect::top(Val::Px(24.)),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                })
+                .with_children(|parent| {
+                    spawn_text_button(parent, asset_server, "Restart", RestartButton);
+                    spawn_text_button(parent, asset_server, "Back to editor", ExitPlayButton);
+                });
+        });
+}
+
+fn spawn_text_button(
+    parent: &mut ChildBuilder,
+    asset_server: &AssetServer,
+    label: &str,
+    components: impl Bundle,
+) {
+    parent
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    min_size: Size::new(Val::Px(72.), Val::Px(48.)),
+                    margin: UiRect::horizontal(Val::Px(3.)),
+                    padding: UiRect::horizontal(Val::Px(12.)),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..Default::default()
+                },
+                background_color: NORMAL_BUTTON_COLOR.into(),
+                ..Default::default()
+            },
+            components,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle {
+                text: Text::from_section(
+                    label,
+                    TextStyle {
+                        font: asset_server.load(FONT),
+                        font_size: 24.,
+                        color: Color::rgb(0.9, 0.9, 0.9),
+                    },
+                ),
+                focus_policy: FocusPolicy::Pass,
+                ..Default::default()
+            });
+        });
+}
+
+#[allow(clippy::type_complexity)]
+pub fn play_buttons(
+    play_query: Query<&Interaction, (With<PlayButton>, Changed<Interaction>)>,
+    exit_query: Query<&Interaction, (With<ExitPlayButton>, Changed<Interaction>)>,
+    restart_query: Query<&Interaction, (With<RestartButton>, Changed<Interaction>)>,
+    keyboard_input: Res<Input<KeyCode>>,
+    play_session: Option<Res<PlaySession>>,
+    mut play_mode_events: EventWriter<PlayModeEvent>,
+) {
+    let clicked = |interaction: &Interaction| *interaction == Interaction::Clicked;
+    if play_query.iter().any(clicked) {
+        play_mode_events.send(PlayModeEvent::Start);
+    }
+    if exit_query.iter().any(clicked)
+        || (play_session.is_some() && keyboard_input.just_pressed(KeyCode::Escape))
+    {
+        play_mode_events.send(PlayModeEvent::Stop);
+    }
+    if restart_query.iter().any(clicked) {
+        play_mode_events.send(PlayModeEvent::Restart);
+    }
+}
+
+/// Saves the input recorded so far and starts the playback of picked replay files.
+pub fn replay_buttons(
+    save_query: Query<&Interaction, (With<SaveReplayButton>, Changed<Interaction>)>,
+    load_query: Query<&Interaction, (With<LoadReplayButton>, Changed<Interaction>)>,
+    input_replay: Res<InputReplay>,
+    replay_loading: Res<ReplayLoading>,
+    mut play_mode_events: EventWriter<PlayModeEvent>,
+) {
+    let clicked = |interaction: &Interaction| *interaction == Interaction::Clicked;
+    if save_query.iter().any(clicked) {
+        if let Some(replay) = input_replay.to_replay() {
+            match replay.serialize() {
+                Ok(data) => {
+                    let file_name = replay.course.metadata.file_name();
+                    let stem = file_name.trim_end_matches(".shrm");
+                    crate::game::download(&data, &format!("{stem}.replay"));
+                }
+                Err(err) => error!("Replay could not be saved: {err}"),
+            }
+        }
+    }
+    if load_query.iter().any(clicked) {
+        let replay_loading = replay_loading.0.clone();
+        crate::open_file(move |buffer| {
+            let replay = Replay::deserialize(&buffer)
+                .map_err(|err| format!("Replay could not be read: {err}"));
+            *replay_loading.write().unwrap() = Some(replay);
+        });
+    }
+
+    let replay = replay_loading.0.write().unwrap().take();
+    match replay {
+        Some(Ok(replay)) => play_mode_events.send(PlayModeEvent::Replay(Box::new(replay))),
+        Some(Err(reason)) => {
+            let window = web_sys::window().unwrap();
+            window.alert_with_message(&reason).unwrap();
+        }
+        None => {}
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub fn update_play_ui(
+    mut hud_query: Query<&mut Style, (With<PlayHud>, Without<PlayResultScreen>)>,
+    mut result_query: Query<&mut Style, (With<PlayResultScreen>, Without<PlayHud>)>,
+    mut lives_query: Query<&mut Text, (With<LivesText>, Without<PlayResultText>)>,
+    mut result_text_query: Query<&mut Text, (With<PlayResultText>, Without<LivesText>)>,
+    play_session: Option<Res<PlaySession>>,
+    input_replay: Res<InputReplay>,
+) {
+    let result = match play_session
+        .as_deref()
+        .map(|play_session| &play_session.state)
+    {
+        Some(PlayState::GameOver) => Some("Game over".to_string()),
+        Some(PlayState::Cleared { clear_time }) => {
+            Some(format!("Course clear! {:.2}s", clear_time.as_secs_f32()))
+        }
+        _ => None,
+    };
+    let hud_display = if play_session.is_some() {
+        Display::Flex
+    } else {
+        Display::None
+    };
+    let result_display = if result.is_some() {
+        Display::Flex
+    } else {
+        Display::None
+    };
+    for (mut style, display) in hud_query
+        .iter_mut()
+        .map(|style| (style, hud_display))
+        .chain(result_query.iter_mut().map(|style| (style, result_display)))
+    {
+        if style.display != display {
+            style.display = display;
+        }
+    }
+
+    if let (Some(result), Ok(mut text)) = (result, result_text_query.get_single_mut()) {
+        set_text(&mut text, result);
+    }
+    if let (Some(play_session), Ok(mut text)) = (play_session, lives_query.get_single_mut()) {
+        let suffix = if input_replay.is_playing_back() {
+            " (replay)"
+        } else {
+            ""
+        };
+        set_text(&mut text, format!("Lives: {}{suffix}", play_session.lives));
+    }
+}
+
+fn set_text(text: &mut Text, value: String) {
+    if text.sections[0].value != value {
+        text.sections[0].value = value;
+    }
+}
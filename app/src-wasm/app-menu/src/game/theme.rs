@@ -0,0 +1,165 @@
+use app_config::*;
+use app_core::{ChangeThemeEvent, CourseRes, GameModeEdit};
+use bevy::{prelude::*, ui::FocusPolicy};
+use shrm_core::ThemeVariant;
+
+const FONT: &str = "fonts/FiraSans-Bold.ttf";
+
+#[derive(Component)]
+pub struct ThemeButton;
+
+#[derive(Component)]
+pub struct ThemeButtonText;
+
+#[derive(Component)]
+pub struct ThemePanel;
+
+#[derive(Component)]
+pub struct ThemeOption(ThemeVariant);
+
+fn theme_label(theme: &ThemeVariant) -> &'static str {
+    match theme {
+        ThemeVariant::Plain => "Plain",
+        ThemeVariant::Underground => "Underground",
+        ThemeVariant::Water => "Water",
+        ThemeVariant::HauntedHouse => "Ghost House",
+        ThemeVariant::Castle => "Castle",
+        ThemeVariant::Woods => "Woods",
+        ThemeVariant::Desert => "Desert",
+        ThemeVariant::Snow => "Snow",
+        ThemeVariant::Airship => "Airship",
+    }
+}
+
+pub(crate) fn spawn_theme_button(
+    parent: &mut ChildBuilder,
+    asset_server: &AssetServer,
+    theme: &ThemeVariant,
+) {
+    parent
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    size: Size::new(Val::Px(128.), Val::Px(48.)),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..Default::default()
+                },
+                background_color: NORMAL_BUTTON_COLOR.into(),
+                ..Default::default()
+            },
+            ThemeButton,
+            GameModeEdit,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle {
+                    text: Text::from_section(
+                        theme_label(theme),
+                        TextStyle {
+                            font: asset_server.load(FONT),
+                            font_size: 20.,
+                            color: Color::rgb(0.9, 0.9, 0.9),
+                        },
+                    ),
+                    focus_policy: FocusPolicy::Pass,
+                    ..Default::default()
+                },
+                ThemeButtonText,
+            ));
+        });
+}
+
+fn spawn_theme_panel(commands: &mut Commands, asset_server: &AssetServer) {
+    let text_style = TextStyle {
+        font: asset_server.load(FONT),
+        font_size: 20.,
+        color: Color::rgb(0.9, 0.9, 0.9),
+    };
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    position: UiRect {
+                        top: Val::Px(66.),
+                        right: Val::Px(12.),
+                        ..Default::default()
+                    },
+                    flex_direction: FlexDirection::ColumnReverse,
+                    padding: UiRect::all(Val::Px(8.)),
+                    ..Default::default()
+                },
+                background_color: PANEL_COLOR.into(),
+                ..Default::default()
+            },
+            // blocks tile placement below the panel
+            Interaction::default(),
+            ThemePanel,
+            GameModeEdit,
+        ))
+        .with_children(|parent| {
+            for theme in enum_iterator::all::<ThemeVariant>() {
+                parent
+                    .spawn((
+                        ButtonBundle {
+                            style: Style {
+                                size: Size::new(Val::Px(160.), Val::Px(32.)),
+                                margin: UiRect::bottom(Val::Px(4.)),
+                                justify_content: JustifyContent::Center,
+                                align_items: AlignItems::Center,
+                                ..Default::default()
+                            },
+                            background_color: NORMAL_BUTTON_COLOR.into(),
+                            ..Default::default()
+                        },
+                        ThemeOption(theme.clone()),
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn(TextBundle {
+                            text: Text::from_section(theme_label(&theme), text_style.clone()),
+                            focus_policy: FocusPolicy::Pass,
+                            ..Default::default()
+                        });
+                    });
+            }
+        });
+}
+
+/// Opens the theme picker and changes the theme of the loaded area to the picked one.
+#[allow(clippy::too_many_arguments)]
+pub fn pick_theme(
+    mut commands: Commands,
+    button_query: Query<&Interaction, (With<ThemeButton>, Changed<Interaction>)>,
+    option_query: Query<(&Interaction, &ThemeOption), Changed<Interaction>>,
+    panel_query: Query<Entity, With<ThemePanel>>,
+    mut text_query: Query<&mut Text, With<ThemeButtonText>>,
+    course: Res<CourseRes>,
+    asset_server: Res<AssetServer>,
+    mut change_theme_events: EventWriter<ChangeThemeEvent>,
+) {
+    if let Some(&Interaction::Clicked) = button_query.iter().next() {
+        if let Some(panel) = panel_query.iter().next() {
+            commands.entity(panel).despawn_recursive();
+        } else {
+            spawn_theme_panel(&mut commands, &asset_server);
+        }
+    }
+    for (interaction, ThemeOption(theme)) in option_query.iter() {
+        if *interaction == Interaction::Clicked {
+            change_theme_events.send(ChangeThemeEvent(theme.clone()));
+            for panel in panel_query.iter() {
+                commands.entity(panel).despawn_recursive();
+            }
+        }
+    }
+    // the theme also changes when switching areas
+    if course.is_changed() {
+        if let Ok(mut text) = text_query.get_single_mut() {
+            if text.sections[0].value != theme_label(&course.theme) {
+                text.sections[0].value = theme_label(&course.theme).to_string();
+            }
+        }
+    }
+}
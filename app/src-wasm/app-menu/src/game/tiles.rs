@@ -1,15 +1,91 @@
+use super::metadata::MetadataEditor;
 use app_config::*;
-use app_core::{GameModeEdit, SelectedTile, TileComponent, TilePlacePreview, TileSpriteHandles};
-use bevy::{prelude::*, ui::FocusPolicy};
-use shrm_core::{GroundVariant, TileVariant};
+use app_core::{
+    CourseRes, GameMode, GameModeEdit, SelectedTile, ThemedTileSpriteHandles, TileComponent,
+    TilePlacePreview, TileSpriteHandles,
+};
+use bevy::{asset::LoadState, prelude::*, ui::FocusPolicy};
+use shrm_core::{ThemeVariant, TileCategory, TileVariant};
+
+/// Tiles shown at once, each selectable with its number key.
+const PALETTE_PAGE_SIZE: usize = 9;
+
+const SLOT_KEYS: [[KeyCode; 2]; PALETTE_PAGE_SIZE] = [
+    [KeyCode::Key1, KeyCode::Numpad1],
+    [KeyCode::Key2, KeyCode::Numpad2],
+    [KeyCode::Key3, KeyCode::Numpad3],
+    [KeyCode::Key4, KeyCode::Numpad4],
+    [KeyCode::Key5, KeyCode::Numpad5],
+    [KeyCode::Key6, KeyCode::Numpad6],
+    [KeyCode::Key7, KeyCode::Numpad7],
+    [KeyCode::Key8, KeyCode::Numpad8],
+    [KeyCode::Key9, KeyCode::Numpad9],
+];
 
 #[derive(Component)]
 pub struct SelectedTileButton(pub bool);
 
+fn category_label(category: TileCategory) -> &'static str {
+    match category {
+        TileCategory::Terrain => "Terrain",
+        TileCategory::Blocks => "Blocks",
+        TileCategory::Gizmos => "Gizmos",
+    }
+}
+
+fn tile_label(tile: &TileVariant) -> &'static str {
+    match tile {
+        TileVariant::Ground(_) => "Ground",
+        TileVariant::HardBlock => "Hard Block",
+        TileVariant::RotatingBlock => "Rotating Block",
+        TileVariant::DonutBlock => "Donut Block",
+        TileVariant::CloudBlock => "Cloud Block",
+    }
+}
+
 pub struct SelectTileEvent(pub Entity);
 
+/// Category and page of the palette that is shown.
+#[derive(Default, Resource)]
+pub struct Palette {
+    pub category: TileCategory,
+    page: usize,
+}
+
+impl Palette {
+    fn tiles(&self) -> Vec<TileVariant> {
+        TileVariant::palette()
+            .filter(|tile| tile.category() == self.category)
+            .collect()
+    }
+
+    fn page_count(&self) -> usize {
+        self.tiles().len().saturating_sub(1) / PALETTE_PAGE_SIZE + 1
+    }
+}
+
+/// Holds the tile buttons of the current page.
+#[derive(Component)]
+pub struct PaletteSlots;
+
+/// Position of a tile button on its page.
+#[derive(Component)]
+pub struct PaletteSlot(usize);
+
+#[derive(Component)]
+pub struct PaletteCategoryButton(pub TileCategory);
+
+#[derive(Component)]
+pub struct PalettePageButton(isize);
+
+#[derive(Component)]
+pub struct TileTooltip;
+
+#[derive(Component)]
+pub struct TileTooltipText;
+
 macro_rules! add_tile_button {
-    ( $parent:expr, $color:expr, $sprite_handles:expr, $tile:expr, $is_selected:expr ) => {
+    ( $parent:expr, $color:expr, $image:expr, $tile:expr, $slot:expr, $is_selected:expr ) => {
         $parent
             .spawn((
                 ButtonBundle {
@@ -23,11 +99,12 @@ macro_rules! add_tile_button {
                     ..Default::default()
                 },
                 $tile,
+                PaletteSlot($slot),
                 SelectedTileButton($is_selected),
             ))
             .with_children(|parent| {
                 parent.spawn(ImageBundle {
-                    image: UiImage($sprite_handles.0.get(&$tile.0).unwrap().clone()),
+                    image: UiImage($image),
                     transform: Transform {
                         scale: Vec3::new(TILE_SIZE, TILE_SIZE, 0.),
                         ..Default::default()
@@ -43,7 +120,13 @@ macro_rules! add_tile_button {
     };
 }
 
-pub(crate) fn spawn_tile_buttons(commands: &mut Commands, tile_sprite_handles: &TileSpriteHandles) {
+pub(crate) fn spawn_tile_buttons(commands: &mut Commands, asset_server: &AssetServer) {
+    let text_style = TextStyle {
+        font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+        font_size: 20.,
+        color: Color::rgb(0.9, 0.9, 0.9),
+    };
+
     commands
         .spawn((
             NodeBundle {
@@ -64,42 +147,237 @@ pub(crate) fn spawn_tile_buttons(commands: &mut Commands, tile_sprite_handles: &
             GameModeEdit,
         ))
         .with_children(|parent| {
-            add_tile_button!(
-                parent,
-                SELECTED_BUTTON_COLOR,
-                tile_sprite_handles,
-                TileComponent(TileVariant::Ground(GroundVariant::default())),
-                true
-            );
-            add_tile_button!(
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::ColumnReverse,
+                        margin: UiRect::right(Val::Px(6.)),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                })
+                .with_children(|parent| {
+                    for category in enum_iterator::all::<TileCategory>() {
+                        spawn_text_button(
+                            parent,
+                            category_label(category),
+                            &text_style,
+                            Size::new(Val::Px(80.), Val::Px(16.)),
+                            PaletteCategoryButton(category),
+                        );
+                    }
+                });
+            spawn_text_button(
                 parent,
-                NORMAL_BUTTON_COLOR,
-                tile_sprite_handles,
-                TileComponent(TileVariant::HardBlock),
-                false
+                "<",
+                &text_style,
+                Size::new(Val::Px(24.), Val::Px(48.)),
+                PalettePageButton(-1),
             );
-            add_tile_button!(
+            parent.spawn((
+                NodeBundle {
+                    style: Style {
+                        min_size: Size::new(Val::Px(48.), Val::Px(48.)),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                PaletteSlots,
+            ));
+            spawn_text_button(
                 parent,
-                NORMAL_BUTTON_COLOR,
-                tile_sprite_handles,
-                TileComponent(TileVariant::RotatingBlock),
-                false
+                ">",
+                &text_style,
+                Size::new(Val::Px(24.), Val::Px(48.)),
+                PalettePageButton(1),
             );
-            add_tile_button!(
-                parent,
-                NORMAL_BUTTON_COLOR,
-                tile_sprite_handles,
-                TileComponent(TileVariant::DonutBlock),
-                false
+            parent.spawn(NodeBundle {
+                style: Style {
+                    size: Size::new(Val::Px(12.), Val::Auto),
+                    ..Default::default()
+                },
+                ..Default::default()
+            });
+            super::tools::spawn_tool_buttons(parent, asset_server);
+        });
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    display: Display::None,
+                    position_type: PositionType::Absolute,
+                    padding: UiRect::all(Val::Px(4.)),
+                    ..Default::default()
+                },
+                background_color: PANEL_COLOR.into(),
+                focus_policy: FocusPolicy::Pass,
+                ..Default::default()
+            },
+            TileTooltip,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section("", text_style.clone()),
+                TileTooltipText,
+            ));
+        });
+}
+
+fn spawn_text_button(
+    parent: &mut ChildBuilder,
+    label: &str,
+    text_style: &TextStyle,
+    size: Size,
+    component: impl Component,
+) {
+    parent
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    size,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..Default::default()
+                },
+                background_color: NORMAL_BUTTON_COLOR.into(),
+                ..Default::default()
+            },
+            component,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle {
+                text: Text::from_section(
+                    label,
+                    TextStyle {
+                        font_size: 16.,
+                        ..text_style.clone()
+                    },
+                ),
+                focus_policy: FocusPolicy::Pass,
+                ..Default::default()
+            });
+        });
+}
+
+/// The icon of `tile` in `theme`, or the plain one as long as the themed one is not loaded.
+fn tile_icon(
+    tile_sprite_handles: &TileSpriteHandles,
+    themed_tile_sprite_handles: &ThemedTileSpriteHandles,
+    asset_server: &AssetServer,
+    theme: &ThemeVariant,
+    tile: &TileVariant,
+) -> Handle<Image> {
+    themed_tile_sprite_handles
+        .0
+        .get(&(theme.clone(), tile.clone()))
+        .filter(|handle| asset_server.get_load_state(handle.id()) == LoadState::Loaded)
+        .unwrap_or_else(|| &tile_sprite_handles.0[tile])
+        .clone()
+}
+
+/// Spawns the tile buttons of the current page whenever the page or the theme changes,
+/// and once more for every themed icon that finished loading.
+#[allow(clippy::too_many_arguments)]
+#[allow(clippy::type_complexity)]
+pub fn update_palette(
+    mut commands: Commands,
+    slots_query: Query<Entity, With<PaletteSlots>>,
+    added_query: Query<(), Added<PaletteSlots>>,
+    mut category_query: Query<(&PaletteCategoryButton, &mut BackgroundColor)>,
+    mut page_query: Query<&mut Style, With<PalettePageButton>>,
+    palette: Res<Palette>,
+    selected_tile: Res<SelectedTile>,
+    tile_sprite_handles: Res<TileSpriteHandles>,
+    themed_tile_sprite_handles: Res<ThemedTileSpriteHandles>,
+    asset_server: Res<AssetServer>,
+    course: Res<CourseRes>,
+    mut icons: Local<Option<(ThemeVariant, usize)>>,
+) {
+    let loaded_icons = themed_tile_sprite_handles
+        .0
+        .iter()
+        .filter(|((theme, _), handle)| {
+            *theme == course.theme && asset_server.get_load_state(handle.id()) == LoadState::Loaded
+        })
+        .count();
+    let is_icons_changed = icons.as_ref() != Some(&(course.theme.clone(), loaded_icons));
+    if !palette.is_changed() && added_query.is_empty() && !is_icons_changed {
+        return;
+    }
+    let slots = if let Ok(slots) = slots_query.get_single() {
+        slots
+    } else {
+        return;
+    };
+    *icons = Some((course.theme.clone(), loaded_icons));
+
+    commands.entity(slots).despawn_descendants();
+    commands.entity(slots).with_children(|parent| {
+        let tiles = palette
+            .tiles()
+            .into_iter()
+            .skip(palette.page * PALETTE_PAGE_SIZE);
+        for (slot, tile) in tiles.take(PALETTE_PAGE_SIZE).enumerate() {
+            let is_selected = selected_tile.0.as_ref() == Some(&tile);
+            let color = if is_selected {
+                SELECTED_BUTTON_COLOR
+            } else {
+                NORMAL_BUTTON_COLOR
+            };
+            let image = tile_icon(
+                &tile_sprite_handles,
+                &themed_tile_sprite_handles,
+                &asset_server,
+                &course.theme,
+                &tile,
             );
             add_tile_button!(
                 parent,
-                NORMAL_BUTTON_COLOR,
-                tile_sprite_handles,
-                TileComponent(TileVariant::CloudBlock),
-                false
+                color,
+                image,
+                TileComponent(tile.clone()),
+                slot,
+                is_selected
             );
-        });
+        }
+    });
+
+    for (PaletteCategoryButton(category), mut color) in category_query.iter_mut() {
+        *color = if *category == palette.category {
+            SELECTED_BUTTON_COLOR
+        } else {
+            NORMAL_BUTTON_COLOR
+        }
+        .into();
+    }
+    let display = if palette.page_count() > 1 {
+        Display::Flex
+    } else {
+        Display::None
+    };
+    for mut style in page_query.iter_mut() {
+        style.display = display;
+    }
+}
+
+pub fn palette_buttons(
+    category_query: Query<(&Interaction, &PaletteCategoryButton), Changed<Interaction>>,
+    page_query: Query<(&Interaction, &PalettePageButton), Changed<Interaction>>,
+    mut palette: ResMut<Palette>,
+) {
+    for (interaction, PaletteCategoryButton(category)) in category_query.iter() {
+        if *interaction == Interaction::Clicked && palette.category != *category {
+            palette.category = *category;
+            palette.page = 0;
+        }
+    }
+    for (interaction, PalettePageButton(step)) in page_query.iter() {
+        if *interaction == Interaction::Clicked {
+            let page_count = palette.page_count() as isize;
+            palette.page = (palette.page as isize + step).rem_euclid(page_count) as usize;
+        }
+    }
 }
 
 pub fn select_tile(
@@ -133,6 +411,44 @@ pub fn select_tile(
     }
 }
 
+/// Selects the tile of the nth slot of the page with the number keys.
+pub fn select_tile_by_key(
+    mut query: Query<(
+        Entity,
+        &PaletteSlot,
+        &TileComponent,
+        &mut BackgroundColor,
+        &mut SelectedTileButton,
+    )>,
+    keyboard_input: Res<Input<KeyCode>>,
+    game_mode: Res<GameMode>,
+    metadata_editor: Res<MetadataEditor>,
+    mut selected_tile: ResMut<SelectedTile>,
+    mut select_tile_event: EventWriter<SelectTileEvent>,
+) {
+    if !matches!(*game_mode, GameMode::Build { is_editing: true }) || metadata_editor.is_focused() {
+        return;
+    }
+    let slot = if let Some(slot) = SLOT_KEYS
+        .iter()
+        .position(|keys| keyboard_input.any_just_pressed(*keys))
+    {
+        slot
+    } else {
+        return;
+    };
+    for (entity, PaletteSlot(button_slot), tile_variant, mut color, mut is_selected) in
+        query.iter_mut()
+    {
+        if *button_slot == slot {
+            selected_tile.0 = Some(tile_variant.0.clone());
+            *color = SELECTED_BUTTON_COLOR.into();
+            is_selected.0 = true;
+            select_tile_event.send(SelectTileEvent(entity));
+        }
+    }
+}
+
 pub fn change_after_tile_select(
     mut query: Query<(
         Entity,
@@ -159,3 +475,41 @@ pub fn change_after_tile_select(
         }
     }
 }
+
+/// Shows the name of the hovered tile below the cursor.
+pub fn show_tile_tooltip(
+    query: Query<(&Interaction, &TileComponent)>,
+    mut tooltip_query: Query<&mut Style, With<TileTooltip>>,
+    mut text_query: Query<&mut Text, With<TileTooltipText>>,
+    windows: Res<Windows>,
+) {
+    let mut style = if let Ok(style) = tooltip_query.get_single_mut() {
+        style
+    } else {
+        return;
+    };
+    let cursor_position = windows.get_primary().unwrap().cursor_position();
+    let hovered = query
+        .iter()
+        .find(|(interaction, _)| **interaction != Interaction::None)
+        .map(|(_, TileComponent(tile))| tile);
+
+    if let (Some(tile), Some(cursor_position)) = (hovered, cursor_position) {
+        if let Ok(mut text) = text_query.get_single_mut() {
+            if text.sections[0].value != tile_label(tile) {
+                text.sections[0].value = tile_label(tile).to_string();
+            }
+        }
+        let position = UiRect {
+            left: Val::Px(cursor_position.x + 12.),
+            bottom: Val::Px(cursor_position.y - 36.),
+            ..Default::default()
+        };
+        if style.display != Display::Flex || style.position != position {
+            style.display = Display::Flex;
+            style.position = position;
+        }
+    } else if style.display != Display::None {
+        style.display = Display::None;
+    }
+}
@@ -0,0 +1,88 @@
+use app_config::*;
+use app_core::{EditorTool, TilePlacePreview, ToolStart};
+use bevy::{prelude::*, ui::FocusPolicy};
+
+#[derive(Component)]
+pub struct ToolButton(pub EditorTool);
+
+pub(crate) fn spawn_tool_buttons(parent: &mut ChildBuilder, asset_server: &AssetServer) {
+    for (label, tool) in [
+        ("Pen", EditorTool::Pencil),
+        ("Line", EditorTool::Line),
+        ("Rect", EditorTool::Rect),
+        ("Frame", EditorTool::HollowRect),
+        ("Fill", EditorTool::Fill),
+        ("Select", EditorTool::Select),
+    ] {
+        let color = if tool == EditorTool::default() {
+            SELECTED_BUTTON_COLOR
+        } else {
+            NORMAL_BUTTON_COLOR
+        };
+        parent
+            .spawn((
+                ButtonBundle {
+                    style: Style {
+                        size: Size::new(Val::Px(56.), Val::Px(48.)),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..Default::default()
+                    },
+                    background_color: color.into(),
+                    ..Default::default()
+                },
+                ToolButton(tool),
+            ))
+            .with_children(|parent| {
+                parent.spawn(TextBundle {
+                    text: Text::from_section(
+                        label,
+                        TextStyle {
+                            font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+                            font_size: 20.,
+                            color: Color::rgb(0.9, 0.9, 0.9),
+                        },
+                    ),
+                    focus_policy: FocusPolicy::Pass,
+                    ..Default::default()
+                });
+            });
+    }
+}
+
+pub fn select_tool(
+    mut commands: Commands,
+    mut query: Query<(&Interaction, &ToolButton, &mut BackgroundColor)>,
+    mut tool: ResMut<EditorTool>,
+    mut tool_start: ResMut<ToolStart>,
+    mut tile_place_preview: ResMut<TilePlacePreview>,
+) {
+    let clicked = query
+        .iter()
+        .find(|(interaction, _, _)| **interaction == Interaction::Clicked)
+        .map(|(_, ToolButton(tool), _)| *tool);
+    let selected = if let Some(selected) = clicked {
+        selected
+    } else {
+        return;
+    };
+    if *tool == selected {
+        return;
+    }
+    *tool = selected;
+    tool_start.0 = None;
+    if let Some((entity, _)) = tile_place_preview.0.take() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    for (interaction, ToolButton(button_tool), mut color) in query.iter_mut() {
+        *color = if *button_tool == selected {
+            SELECTED_BUTTON_COLOR
+        } else if *interaction == Interaction::Hovered {
+            HOVERED_BUTTON_COLOR
+        } else {
+            NORMAL_BUTTON_COLOR
+        }
+        .into();
+    }
+}
@@ -4,14 +4,28 @@ pub(crate) use game::tiles::SelectTileEvent;
 
 use app_config::*;
 use app_core::{
-    AppLabel, AppStage, AppState, CourseLoading, CourseRes, GroundTileUpdateEvent,
-    ObjectSpriteHandles, SelectedTile, TileComponent, UiButtonSpriteHandles, UiButtonVariant,
+    load_autosave, AppLabel, AppStage, AppState, CourseLoading, CourseRes, EditorTool,
+    GroundTileUpdateEvent, ObjectSpriteHandles, SelectedTile, TileComponent, UiButtonSpriteHandles,
+    UiButtonVariant,
 };
 use bevy::{prelude::*, ui::FocusPolicy};
 use game::{
     export,
-    tiles::{change_after_tile_select, select_tile},
+    metadata::{
+        edit_metadata, focus_metadata_field, toggle_metadata_panel, update_text_input_focus,
+        MetadataEditor,
+    },
+    minimap::{click_minimap, update_minimap, update_minimap_markers},
+    physics::{physics_panel_buttons, toggle_physics_panel, update_physics_panel},
+    play::{play_buttons, replay_buttons, update_play_ui, ReplayLoading},
+    switch_area,
+    theme::pick_theme,
+    tiles::{
+        change_after_tile_select, palette_buttons, select_tile, select_tile_by_key,
+        show_tile_tooltip, update_palette, Palette, PaletteCategoryButton,
+    },
     toggle_game_mode,
+    tools::{select_tool, ToolButton},
 };
 use js_sys::{ArrayBuffer, Uint8Array};
 use shrm_core::{Course, ThemeVariant};
@@ -27,6 +41,9 @@ struct MainMenuBuildButton;
 #[derive(Component)]
 struct MainMenuImportButton;
 
+#[derive(Component)]
+struct MainMenuRestoreButton;
+
 #[derive(SystemLabel, Debug, Clone, PartialEq, Eq, Hash)]
 pub enum MenuLabel {
     SelectTile,
@@ -37,6 +54,9 @@ pub struct MenuPlugin;
 impl Plugin for MenuPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<SelectTileEvent>()
+            .init_resource::<MetadataEditor>()
+            .init_resource::<Palette>()
+            .init_resource::<ReplayLoading>()
             .add_system_set(SystemSet::on_enter(AppState::Menu).with_system(setup_menu))
             .add_system_set(SystemSet::on_exit(AppState::Menu).with_system(exit_menu))
             .add_system_set(
@@ -49,9 +69,40 @@ impl Plugin for MenuPlugin {
                 AppStage::PlayerInput,
                 SystemSet::on_update(AppState::Game)
                     .with_system(select_tile.label(MenuLabel::SelectTile))
+                    .with_system(select_tile_by_key.label(MenuLabel::SelectTile))
+                    .with_system(palette_buttons)
                     .with_system(change_after_tile_select.after(MenuLabel::SelectTile))
                     .with_system(export)
-                    .with_system(toggle_game_mode),
+                    .with_system(switch_area)
+                    .with_system(pick_theme)
+                    .with_system(toggle_game_mode)
+                    .with_system(toggle_metadata_panel)
+                    .with_system(focus_metadata_field)
+                    .with_system(
+                        update_text_input_focus
+                            .after(toggle_metadata_panel)
+                            .after(focus_metadata_field),
+                    )
+                    .with_system(play_buttons)
+                    .with_system(replay_buttons)
+                    .with_system(toggle_physics_panel)
+                    .with_system(physics_panel_buttons)
+                    .with_system(select_tool),
+            )
+            .add_system_set(
+                SystemSet::on_update(AppState::Game)
+                    .with_system(edit_metadata)
+                    .with_system(update_play_ui)
+                    .with_system(click_minimap)
+                    .with_system(update_palette)
+                    .with_system(show_tile_tooltip)
+                    .with_system(update_physics_panel),
+            )
+            .add_system_set_to_stage(
+                CoreStage::Last,
+                SystemSet::on_update(AppState::Game)
+                    .with_system(update_minimap)
+                    .with_system(update_minimap_markers),
             )
             .add_system(on_hover);
     }
@@ -60,12 +111,32 @@ impl Plugin for MenuPlugin {
 #[allow(clippy::type_complexity)]
 fn on_hover(
     mut query: Query<
-        (&Interaction, &mut BackgroundColor, Option<&TileComponent>),
+        (
+            &Interaction,
+            &mut BackgroundColor,
+            Option<&TileComponent>,
+            Option<&ToolButton>,
+            Option<&PaletteCategoryButton>,
+        ),
         (Changed<Interaction>, With<Button>),
     >,
     selected_tile: Res<SelectedTile>,
+    tool: Res<EditorTool>,
+    palette: Res<Palette>,
 ) {
-    for (interaction, mut color, tile_variant) in query.iter_mut() {
+    for (interaction, mut color, tile_variant, tool_button, category_button) in query.iter_mut() {
+        if let Some(PaletteCategoryButton(category)) = category_button {
+            if *category == palette.category {
+                *color = SELECTED_BUTTON_COLOR.into();
+                continue;
+            }
+        }
+        if let Some(ToolButton(button_tool)) = tool_button {
+            if *button_tool == *tool {
+                *color = SELECTED_BUTTON_COLOR.into();
+                continue;
+            }
+        }
         if let Some(selected_tile) = &selected_tile.0 {
             if let Some(tile_variant) = tile_variant {
                 if &tile_variant.0 == selected_tile {
@@ -92,6 +163,7 @@ fn start_game(
     mut commands: Commands,
     build_query: Query<&Interaction, (Changed<Interaction>, With<MainMenuBuildButton>)>,
     import_query: Query<&Interaction, (Changed<Interaction>, With<MainMenuImportButton>)>,
+    restore_query: Query<&Interaction, (Changed<Interaction>, With<MainMenuRestoreButton>)>,
     mut state: ResMut<State<AppState>>,
     asset_server: Res<AssetServer>,
     mut texture_atlases: ResMut<Assets<TextureAtlas>>,
@@ -113,59 +185,85 @@ fn start_game(
     }
     if let Some(&Interaction::Clicked) = import_query.into_iter().next() {
         state.set(AppState::Load).unwrap();
-        let window = web_sys::window().unwrap();
-        let document = window.document().unwrap();
-        let file_input: HtmlInputElement =
-            document.create_element("input").unwrap().unchecked_into();
-        file_input.set_attribute("type", "file").unwrap();
-        file_input
-            .set_attribute("style", "{\"display\": \"none\"}")
-            .unwrap();
-        let this = file_input.clone();
-
         let course_res = course_loading.0.clone();
-        // TODO enable weakrefs to prevent memory leak
-        // https://rustwasm.github.io/wasm-bindgen/api/wasm_bindgen/closure/struct.Closure.html#method.into_js_value
-        let input_closure = Closure::once(move || {
-            if let Some(files) = this.files() {
-                if files.length() == 0 {
-                    return;
-                }
-                let file = files.item(0).unwrap();
-                let read_closure = Closure::once(move |val: JsValue| {
-                    let buffer: ArrayBuffer = val.unchecked_into();
-                    let buffer = Uint8Array::new(&buffer);
-                    let buffer = buffer.to_vec();
+        open_file(move |buffer| {
+            *course_res.write().unwrap() = Some(read_course(buffer));
+        });
+    }
+    if let Some(&Interaction::Clicked) = restore_query.into_iter().next() {
+        if let Some(buffer) = load_autosave() {
+            state.set(AppState::Load).unwrap();
+            *course_loading.0.write().unwrap() = Some(read_course(buffer));
+        }
+    }
+}
 
-                    let course = Course::deserialize(buffer).unwrap();
+/// Lets the user pick a file and calls `on_load` with its content.
+pub(crate) fn open_file(on_load: impl FnOnce(Vec<u8>) + 'static) {
+    let window = web_sys::window().unwrap();
+    let document = window.document().unwrap();
+    let file_input: HtmlInputElement = document.create_element("input").unwrap().unchecked_into();
+    file_input.set_attribute("type", "file").unwrap();
+    file_input
+        .set_attribute("style", "{\"display\": \"none\"}")
+        .unwrap();
+    let this = file_input.clone();
 
-                    *course_res.write().unwrap() = Some(course);
-                });
+    // TODO enable weakrefs to prevent memory leak
+    // https://rustwasm.github.io/wasm-bindgen/api/wasm_bindgen/closure/struct.Closure.html#method.into_js_value
+    let input_closure = Closure::once(move || {
+        if let Some(files) = this.files() {
+            if files.length() == 0 {
+                return;
+            }
+            let file = files.item(0).unwrap();
+            let read_closure = Closure::once(move |val: JsValue| {
+                let buffer: ArrayBuffer = val.unchecked_into();
+                let buffer = Uint8Array::new(&buffer);
+                let buffer = buffer.to_vec();
 
-                // This is ok, because JS Promises don't need to be awaited
-                #[allow(unused_must_use)]
-                {
-                    file.array_buffer().then(&read_closure);
-                }
-                read_closure.forget();
+                on_load(buffer);
+            });
+
+            // This is ok, because JS Promises don't need to be awaited
+            #[allow(unused_must_use)]
+            {
+                file.array_buffer().then(&read_closure);
             }
-        });
-        file_input
-            .add_event_listener_with_callback("change", input_closure.as_ref().unchecked_ref())
-            .unwrap();
-        document.body().unwrap().append_child(&file_input).unwrap();
-        file_input.unchecked_ref::<HtmlElement>().click();
-        input_closure.forget();
-
-        let closure = Closure::<dyn FnMut()>::new(move || {
-            let body = web_sys::window().unwrap().document().unwrap().body();
-            body.unwrap().remove_child(&file_input).unwrap();
-        });
-        window
-            .set_timeout_with_callback(closure.as_ref().unchecked_ref())
-            .unwrap();
-        closure.forget();
-    }
+            read_closure.forget();
+        }
+    });
+    file_input
+        .add_event_listener_with_callback("change", input_closure.as_ref().unchecked_ref())
+        .unwrap();
+    document.body().unwrap().append_child(&file_input).unwrap();
+    file_input.unchecked_ref::<HtmlElement>().click();
+    input_closure.forget();
+
+    let closure = Closure::<dyn FnMut()>::new(move || {
+        let body = web_sys::window().unwrap().document().unwrap().body();
+        body.unwrap().remove_child(&file_input).unwrap();
+    });
+    window
+        .set_timeout_with_callback(closure.as_ref().unchecked_ref())
+        .unwrap();
+    closure.forget();
+}
+
+fn read_course(buffer: Vec<u8>) -> Result<Course, String> {
+    Course::deserialize(buffer)
+        .map_err(|err| format!("Course could not be read: {err}"))
+        .and_then(|course| match course.validate() {
+            Ok(()) => Ok(course),
+            Err(errors) => Err(format!(
+                "Course is invalid:\n{}",
+                errors
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            )),
+        })
 }
 
 macro_rules! add_menu_button {
@@ -244,6 +342,16 @@ fn setup_menu(
         ui_button_sprite_handles,
         asset_server
     );
+    if load_autosave().is_some() {
+        add_menu_button!(
+            "Restore",
+            MainMenuRestoreButton,
+            UiButtonVariant::Import,
+            commands,
+            ui_button_sprite_handles,
+            asset_server
+        );
+    }
 }
 
 fn exit_menu(mut commands: Commands, query: Query<Entity, With<MainMenu>>) {
@@ -0,0 +1,42 @@
+use app_core::InputReplay;
+use bevy::prelude::*;
+use shrm_core::InputFrame;
+
+/// The actions the player systems consume, read from the keyboard or from a replay.
+#[derive(Default, Resource)]
+pub struct PlayerInput {
+    pub current: InputFrame,
+    previous: InputFrame,
+}
+
+impl PlayerInput {
+    pub fn x_axis(&self) -> i8 {
+        -(self.current.left as i8) + self.current.right as i8
+    }
+
+    pub fn y_axis(&self) -> i8 {
+        -(self.current.down as i8) + self.current.up as i8
+    }
+
+    pub fn just_jumped(&self) -> bool {
+        self.current.jump && !self.previous.jump
+    }
+}
+
+pub fn read_input(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut input: ResMut<PlayerInput>,
+    mut replay: ResMut<InputReplay>,
+) {
+    let pressed = |keys: &[KeyCode]| keyboard_input.any_pressed(keys.iter().copied());
+    let frame = replay.next_frame(InputFrame {
+        left: pressed(&[KeyCode::A, KeyCode::Left]),
+        right: pressed(&[KeyCode::D, KeyCode::Right]),
+        up: pressed(&[KeyCode::W, KeyCode::Up]),
+        down: pressed(&[KeyCode::S, KeyCode::Down]),
+        run: pressed(&[KeyCode::LShift, KeyCode::RShift]),
+        jump: pressed(&[KeyCode::Space, KeyCode::Up, KeyCode::W]),
+    });
+    input.previous = input.current;
+    input.current = frame;
+}
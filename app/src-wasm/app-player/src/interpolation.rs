@@ -0,0 +1,52 @@
+use crate::Player;
+use app_config::*;
+use app_core::GameMode;
+use bevy::{prelude::*, time::FixedTimesteps};
+
+/// Smooths the rendering of the player between two steps of the player simulation.
+#[derive(Component, Default)]
+pub struct PlayerInterpolation {
+    previous: Vec2,
+    /// The offset of the rendered player from its simulated position.
+    pub offset: Vec2,
+}
+
+pub fn store_previous_position(
+    mut query: Query<(&Transform, &mut PlayerInterpolation), With<Player>>,
+) {
+    for (transform, mut interpolation) in query.iter_mut() {
+        interpolation.previous = transform.translation.truncate();
+    }
+}
+
+/// Renders the player at the position between the last two steps
+/// that matches the time elapsed since the last one.
+pub fn interpolate_player(
+    mut query: Query<(&Transform, &Children, &mut PlayerInterpolation), With<Player>>,
+    mut child_query: Query<&mut Transform, (With<TextureAtlasSprite>, Without<Player>)>,
+    fixed_timesteps: Res<FixedTimesteps>,
+    game_mode: Res<GameMode>,
+) {
+    let alpha = if let Some(state) = fixed_timesteps.get(PLAYER_TIMESTEP_LABEL) {
+        state.overstep_percentage() as f32
+    } else {
+        return;
+    };
+    for (transform, children, mut interpolation) in query.iter_mut() {
+        let current = transform.translation.truncate();
+        let distance = interpolation.previous - current;
+        // the camera anchor is moved directly while editing
+        let offset = if matches!(*game_mode, GameMode::Build { is_editing: true })
+            || distance.length() > PLAYER_INTERPOLATION_MAX_DISTANCE
+        {
+            Vec2::ZERO
+        } else {
+            distance * (1. - alpha.clamp(0., 1.))
+        };
+        let child = children.first().unwrap();
+        if let Ok(mut child_transform) = child_query.get_mut(*child) {
+            child_transform.translation += (offset - interpolation.offset).extend(0.);
+        }
+        interpolation.offset = offset;
+    }
+}
@@ -1,5 +1,4 @@
-use crate::{Player, PlayerStateEnum, PlayerVelocity};
-use app_config::*;
+use crate::{PhysicsProfile, Player, PlayerFrozen, PlayerInput, PlayerStateEnum, PlayerVelocity};
 use bevy::prelude::*;
 
 pub struct JumpEvent {
@@ -8,23 +7,21 @@ pub struct JumpEvent {
 }
 
 pub fn jump(
-    mut query: Query<(&Player, &mut PlayerVelocity)>,
-    keyboard_input: Res<Input<KeyCode>>,
+    mut query: Query<(&Player, &mut PlayerVelocity), Without<PlayerFrozen>>,
+    input: Res<PlayerInput>,
+    profile: Res<PhysicsProfile>,
     mut jump_event: EventWriter<JumpEvent>,
 ) {
     if let Ok((player, mut vel)) = query.get_single_mut() {
-        let jump = keyboard_input.just_pressed(KeyCode::Space)
-            || keyboard_input.just_pressed(KeyCode::Up)
-            || keyboard_input.just_pressed(KeyCode::W);
-        if !jump {
+        if !input.just_jumped() {
             return;
         }
         if let PlayerStateEnum::Ground { .. } = player.state.state {
-            vel.0.y = JUMP_FORCE;
-            let high_jump_tick = if vel.0.x.abs() > HIGH_JUMP_WALK_THRESHOLD {
-                HIGH_JUMP_TICK_WALK
+            vel.0.y = profile.jump_force;
+            let high_jump_tick = if vel.0.x.abs() > profile.high_jump_walk_threshold {
+                profile.high_jump_tick_walk
             } else {
-                HIGH_JUMP_TICK
+                profile.high_jump_tick
             };
             jump_event.send(JumpEvent {
                 high_jump_tick,
@@ -35,8 +32,9 @@ pub fn jump(
 }
 
 pub fn high_jump(
-    mut query: Query<(&mut Player, &mut PlayerVelocity)>,
-    keyboard_input: Res<Input<KeyCode>>,
+    mut query: Query<(&mut Player, &mut PlayerVelocity), Without<PlayerFrozen>>,
+    input: Res<PlayerInput>,
+    profile: Res<PhysicsProfile>,
 ) {
     if let Ok((mut player, mut vel)) = query.get_single_mut() {
         match player.state.state {
@@ -47,15 +45,11 @@ pub fn high_jump(
                 impulse,
                 fall,
             } if tick < high_jump_tick => {
-                let jump = keyboard_input.pressed(KeyCode::Space)
-                    || keyboard_input.pressed(KeyCode::Up)
-                    || keyboard_input.pressed(KeyCode::W);
-
-                vel.0.y = JUMP_FORCE;
+                vel.0.y = profile.jump_force;
                 player.state.state = PlayerStateEnum::Air {
                     tick: tick + 1,
                     high_jump_tick,
-                    released: !jump,
+                    released: !input.current.jump,
                     impulse,
                     fall,
                 };
@@ -1,12 +1,13 @@
-use crate::Player;
+use crate::{Player, PlayerFrozen};
 use app_config::*;
 use app_core::{GameMode, GameModeToggleEvent};
 use bevy::prelude::*;
 
-pub struct KillEvent(Entity);
+pub struct KillEvent(pub Entity);
 
+#[allow(clippy::type_complexity)]
 pub fn below_surface(
-    mut query: Query<(Entity, &mut Transform), With<Player>>,
+    mut query: Query<(Entity, &mut Transform), (With<Player>, Without<PlayerFrozen>)>,
     mut game_mode: ResMut<GameMode>,
     mut kill_event: EventWriter<KillEvent>,
     mut game_mode_toggle_event: EventWriter<GameModeToggleEvent>,
@@ -25,7 +26,7 @@ pub fn below_surface(
                     game_mode_toggle_event.send(GameModeToggleEvent { is_editing: true });
                 }
             }
-            GameMode::Play => todo!(),
+            GameMode::Play => {}
         }
         kill_event.send(KillEvent(entity));
     }
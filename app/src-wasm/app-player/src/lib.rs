@@ -1,7 +1,10 @@
+mod input;
+mod interpolation;
 mod jump;
 mod kill;
 mod movement;
 mod physics;
+mod profile;
 mod setup;
 mod state_change;
 mod stoop;
@@ -9,21 +12,27 @@ mod touch;
 mod walk;
 
 use app_core::{AppStage, AppState};
-use bevy::prelude::*;
+use bevy::{prelude::*, transform::TransformSystem};
+use input::read_input;
+use interpolation::store_previous_position;
 use jump::{high_jump, jump, jump_to_fall};
 use kill::below_surface;
 use movement::{movement, run};
 use physics::{apply_vel, physics};
+use profile::{apply_physics_profile, load_physics_profile, PhysicsProfileLoader};
 use setup::setup;
 use state_change::state_change;
-use stoop::stoop;
+use stoop::{resize_collider, stoop};
 use touch::touch;
 use walk::{walk_animation, walk_start};
 
+pub use input::PlayerInput;
+pub use interpolation::{interpolate_player, PlayerInterpolation};
 pub use jump::JumpEvent;
 pub use kill::KillEvent;
 pub use movement::{DashTurnEvent, FacingDirectionEvent};
 pub use physics::{GroundIntersectEvent, GroundIntersections, PlayerVelocity};
+pub use profile::{PhysicsProfile, PhysicsValueMut, PHYSICS_PROFILE_PATH};
 pub use stoop::StoopEvent;
 pub use touch::TouchEvent;
 pub use walk::{WalkAnimationTimer, WalkEvent};
@@ -32,7 +41,11 @@ pub struct PlayerPlugin;
 
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
-        app.add_event::<PlayerStateChangeEvent>()
+        app.init_resource::<PlayerInput>()
+            .init_resource::<PhysicsProfile>()
+            .add_asset::<PhysicsProfile>()
+            .init_asset_loader::<PhysicsProfileLoader>()
+            .add_event::<PlayerStateChangeEvent>()
             .add_event::<WalkEvent>()
             .add_event::<FacingDirectionEvent>()
             .add_event::<DashTurnEvent>()
@@ -41,58 +54,44 @@ impl Plugin for PlayerPlugin {
             .add_event::<StoopEvent>()
             .add_event::<TouchEvent>()
             .add_event::<KillEvent>()
+            .add_startup_system(load_physics_profile)
+            .add_system_to_stage(CoreStage::First, apply_physics_profile)
             .add_system_set(SystemSet::on_enter(AppState::Game).with_system(setup))
             .add_system_set_to_stage(
-                CoreStage::First,
-                SystemSet::on_update(AppState::Game).with_system(below_surface),
-            )
-            .add_system_set_to_stage(
-                CoreStage::First,
-                SystemSet::on_update(AppState::Game).with_system(run),
-            )
-            .add_system_set_to_stage(
-                CoreStage::First,
-                SystemSet::on_update(AppState::Game).with_system(jump),
-            )
-            .add_system_set_to_stage(
-                CoreStage::First,
-                SystemSet::on_update(AppState::Game).with_system(high_jump),
-            )
-            .add_system_set_to_stage(
-                CoreStage::First,
-                SystemSet::on_update(AppState::Game).with_system(walk_animation),
-            )
-            .add_system_set_to_stage(
-                AppStage::PlayerInput,
-                SystemSet::on_update(AppState::Game).with_system(movement),
-            )
-            .add_system_set_to_stage(
-                CoreStage::PreUpdate,
-                SystemSet::on_update(AppState::Game).with_system(physics),
-            )
-            .add_system_set_to_stage(
-                CoreStage::PreUpdate,
-                SystemSet::on_update(AppState::Game).with_system(stoop),
-            )
-            .add_system_set_to_stage(
-                AppStage::PrePhysics,
-                SystemSet::on_update(AppState::Game).with_system(apply_vel),
-            )
-            .add_system_set_to_stage(
-                AppStage::PrePhysics,
-                SystemSet::on_update(AppState::Game).with_system(walk_start),
-            )
-            .add_system_set_to_stage(
-                CoreStage::PostUpdate,
-                SystemSet::on_update(AppState::Game).with_system(touch),
+                AppStage::PlayerStep,
+                SystemSet::on_update(AppState::Game)
+                    .with_system(store_previous_position)
+                    .with_system(below_surface)
+                    .with_system(read_input)
+                    .with_system(run.after(read_input))
+                    .with_system(jump.after(read_input))
+                    .with_system(high_jump.after(read_input))
+                    .with_system(walk_animation.after(read_input))
+                    .with_system(
+                        movement
+                            .after(run)
+                            .after(jump)
+                            .after(high_jump)
+                            .after(walk_animation),
+                    )
+                    .with_system(
+                        physics
+                            .after(store_previous_position)
+                            .after(below_surface)
+                            .after(movement),
+                    )
+                    .with_system(stoop.after(movement))
+                    .with_system(apply_vel.after(physics).after(stoop))
+                    .with_system(walk_start.after(physics).after(stoop))
+                    .with_system(touch.after(apply_vel).after(walk_start))
+                    .with_system(jump_to_fall.after(apply_vel).after(walk_start))
+                    .with_system(state_change.after(touch).after(jump_to_fall))
+                    .with_system(resize_collider.after(state_change)),
             )
             .add_system_set_to_stage(
                 CoreStage::PostUpdate,
-                SystemSet::on_update(AppState::Game).with_system(jump_to_fall),
-            )
-            .add_system_set_to_stage(
-                AppStage::StateChange,
-                SystemSet::on_update(AppState::Game).with_system(state_change),
+                SystemSet::on_update(AppState::Game)
+                    .with_system(interpolate_player.before(TransformSystem::TransformPropagate)),
             )
             .add_system_set_to_stage(
                 CoreStage::Last,
@@ -106,6 +105,10 @@ pub struct Player {
     pub state: PlayerState,
 }
 
+/// Ignores input and physics, so that the player can be moved by a scripted sequence.
+#[derive(Component)]
+pub struct PlayerFrozen;
+
 #[derive(Clone, Debug)]
 pub struct PlayerState {
     facing_direction: FacingDirection,
@@ -150,6 +153,29 @@ impl PlayerState {
             is_dash_turning: false,
         }
     }
+
+    pub fn walk() -> Self {
+        PlayerState {
+            state: PlayerStateEnum::Ground {
+                frame: 0,
+                is_walking: true,
+                is_turning: false,
+            },
+            ..Self::float()
+        }
+    }
+
+    pub fn is_grounded(&self) -> bool {
+        matches!(self.state, PlayerStateEnum::Ground { .. })
+    }
+
+    pub fn is_dashing(&self) -> bool {
+        self.is_dashing
+    }
+
+    pub fn is_stooping(&self) -> bool {
+        self.is_stooping
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -1,4 +1,6 @@
-use crate::{Player, PlayerState, PlayerStateEnum, PlayerVelocity};
+use crate::{
+    PhysicsProfile, Player, PlayerFrozen, PlayerInput, PlayerState, PlayerStateEnum, PlayerVelocity,
+};
 use app_config::*;
 use app_core::{GameMode, Ground};
 use bevy::{math::Vec3Swizzles, prelude::*};
@@ -13,11 +15,9 @@ pub struct DashTurnEvent {
     pub is_dash_turning: bool,
 }
 
-pub fn run(mut query: Query<&mut Player>, keyboard_input: Res<Input<KeyCode>>) {
+pub fn run(mut query: Query<&mut Player, Without<PlayerFrozen>>, input: Res<PlayerInput>) {
     if let Ok(mut player) = query.get_single_mut() {
-        let run =
-            keyboard_input.pressed(KeyCode::LShift) || keyboard_input.pressed(KeyCode::RShift);
-        player.state.is_running = run;
+        player.state.is_running = input.current.run;
     }
 }
 
@@ -32,11 +32,12 @@ pub fn movement(
             &Transform,
             &Children,
         ),
-        With<RigidBody>,
+        (With<RigidBody>, Without<PlayerFrozen>),
     >,
     child_query: Query<(Entity, &Collider)>,
     ground_query: Query<&Ground>,
-    keyboard_input: Res<Input<KeyCode>>,
+    input: Res<PlayerInput>,
+    profile: Res<PhysicsProfile>,
     mut facing_direction_events: EventWriter<FacingDirectionEvent>,
     dash_turn_events: EventWriter<DashTurnEvent>,
     ctx: Res<RapierContext>,
@@ -44,17 +45,8 @@ pub fn movement(
 ) {
     if let GameMode::Build { is_editing: true } = *game_mode {
         if let Ok((_, mut vel, _, _, _, _)) = query.get_single_mut() {
-            let left = keyboard_input.pressed(KeyCode::A) || keyboard_input.pressed(KeyCode::Left);
-            let right =
-                keyboard_input.pressed(KeyCode::D) || keyboard_input.pressed(KeyCode::Right);
-            let up = keyboard_input.pressed(KeyCode::W) || keyboard_input.pressed(KeyCode::Up);
-            let down = keyboard_input.pressed(KeyCode::S) || keyboard_input.pressed(KeyCode::Down);
-
-            let x_axis = -(left as i8) + right as i8;
-            let y_axis = -(down as i8) + up as i8;
-
-            vel.0.x = x_axis as f32 * LINVEL_BUILD;
-            vel.0.y = y_axis as f32 * LINVEL_BUILD;
+            vel.0.x = input.x_axis() as f32 * LINVEL_BUILD;
+            vel.0.y = input.y_axis() as f32 * LINVEL_BUILD;
         }
     } else if let Ok((mut player, mut vel, mprops, mut friction, transform, children)) =
         query.get_single_mut()
@@ -79,21 +71,17 @@ pub fn movement(
                     ..
                 } = player.state
                 {
-                    player.state.is_dashing = vel.0.x.abs() > RUN_THRESHOLD;
+                    player.state.is_dashing = vel.0.x.abs() > profile.run_threshold;
                 }
 
-                let left =
-                    keyboard_input.pressed(KeyCode::A) || keyboard_input.pressed(KeyCode::Left);
-                let right =
-                    keyboard_input.pressed(KeyCode::D) || keyboard_input.pressed(KeyCode::Right);
-                let x_axis = -(left as i8) + right as i8;
+                let x_axis = input.x_axis();
 
                 send_dash_turn_event(&player, &vel, dash_turn_events, x_axis);
 
                 let cap = match (player.state.is_stooping, is_running) {
-                    (true, _) => LINVEL_CAP_STOOP,
-                    (false, true) => LINVEL_CAP_RUN,
-                    (false, false) => LINVEL_CAP_WALK,
+                    (true, _) => profile.linvel_cap_stoop,
+                    (false, true) => profile.linvel_cap_run,
+                    (false, false) => profile.linvel_cap_walk,
                 };
                 match x_axis {
                     _ if x_axis > 0 => {
@@ -120,6 +108,7 @@ pub fn movement(
                     collider,
                     &ctx,
                     &ground_query,
+                    &profile,
                     x_axis,
                     is_running,
                 );
@@ -180,16 +169,17 @@ fn update_velocity(
     collider: &Collider,
     ctx: &RapierContext,
     ground_query: &Query<&Ground>,
+    profile: &PhysicsProfile,
     x_axis: i8,
     is_running: bool,
 ) {
     if x_axis != 0 {
         let move_delta = Vec2::new(x_axis as f32, 0.);
         let multiplier = match (&player.state.state, is_running) {
-            (PlayerStateEnum::Air { .. }, false) => MOVE_IMPULSE_MULTIPLIER_AIR,
-            (PlayerStateEnum::Air { .. }, true) => MOVE_IMPULSE_MULTIPLIER_AIR_RUN,
-            (PlayerStateEnum::Ground { .. }, false) => MOVE_IMPULSE_MULTIPLIER_GROUND,
-            (PlayerStateEnum::Ground { .. }, true) => MOVE_IMPULSE_MULTIPLIER_GROUND_RUN,
+            (PlayerStateEnum::Air { .. }, false) => profile.move_impulse_air,
+            (PlayerStateEnum::Air { .. }, true) => profile.move_impulse_air_run,
+            (PlayerStateEnum::Ground { .. }, false) => profile.move_impulse_ground,
+            (PlayerStateEnum::Ground { .. }, true) => profile.move_impulse_ground_run,
         };
 
         #[allow(clippy::blocks_in_if_conditions)]
@@ -1,4 +1,4 @@
-use crate::{Player, WalkAnimationTimer};
+use crate::{PhysicsProfile, Player, PlayerFrozen, WalkAnimationTimer};
 use app_config::*;
 use app_core::{pos_to_world, CourseRes, GameMode, Ground};
 use bevy::{math::Vec3Swizzles, prelude::*, utils::HashSet};
@@ -16,7 +16,7 @@ pub enum GroundIntersectEvent {
 #[derive(Component, Default)]
 pub struct GroundIntersections(pub HashSet<Entity>);
 
-#[allow(clippy::type_complexity)]
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
 pub fn physics(
     mut query: Query<
         (
@@ -27,14 +27,16 @@ pub fn physics(
             &Children,
             &Friction,
             &mut GroundIntersections,
+            &Player,
         ),
-        (With<Player>, With<RigidBody>),
+        (With<RigidBody>, Without<PlayerFrozen>),
     >,
     child_query: Query<(Entity, &Collider)>,
-    ground_query: Query<(&Ground, &Friction)>,
+    ground_query: Query<&Ground>,
     ctx: Res<RapierContext>,
     game_mode: Res<GameMode>,
     course: Res<CourseRes>,
+    profile: Res<PhysicsProfile>,
     ground_intersect_events: EventWriter<GroundIntersectEvent>,
 ) {
     if let GameMode::Build { is_editing: true } = *game_mode {
@@ -48,6 +50,7 @@ pub fn physics(
         children,
         friction,
         mut ground_intersections,
+        player,
     )) = query.get_single_mut()
     {
         let child = children.get(1).unwrap();
@@ -55,7 +58,7 @@ pub fn physics(
         let (ground_friction, ground_colliders) = ground_collision(
             &ctx,
             &mut transform,
-            friction.coefficient,
+            profile.ground_friction * friction.coefficient,
             &ground_query,
             &mut ground_intersections,
             entity,
@@ -83,7 +86,14 @@ pub fn physics(
             collider,
         );
 
-        ground_friction_or_gravity(ground_friction, &mut vel, mprops);
+        // stooping faster than a stooping player moves slides along the ground
+        let ground_friction =
+            if player.state.is_stooping && vel.0.x.abs() > profile.linvel_cap_stoop {
+                ground_friction.map(|friction| friction * profile.crouch_slide_friction)
+            } else {
+                ground_friction
+            };
+        ground_friction_or_gravity(ground_friction, &mut vel, mprops, &profile);
     }
 }
 
@@ -138,18 +148,23 @@ fn apply_pos_vel_limit(transform: &mut Transform, vel: &mut PlayerVelocity, cour
         vel.0.x = 0.;
         transform.translation.x = 0.;
     }
-    let max_x = pos_to_world(course.goal_pos_x + MAX_COURSE_GOAL_OFFSET_X - 2);
+    let max_x = pos_to_world(course.right_edge() - 2);
     if transform.translation.x >= max_x && vel.0.x > 0. {
         vel.0.x = 0.;
         transform.translation.x = max_x;
     }
+    let max_y = pos_to_world(course.height);
+    if transform.translation.y >= max_y && vel.0.y > 0. {
+        vel.0.y = 0.;
+        transform.translation.y = max_y;
+    }
 }
 
 fn collision_detection(
     ctx: &RapierContext,
     transform: &mut Transform,
     vel: &mut PlayerVelocity,
-    ground_query: &Query<(&Ground, &Friction)>,
+    ground_query: &Query<&Ground>,
     entity: Entity,
     collider: &Collider,
 ) {
@@ -233,7 +248,7 @@ fn ground_collision(
     ctx: &RapierContext,
     transform: &mut Transform,
     friction: f32,
-    ground_query: &Query<(&Ground, &Friction)>,
+    ground_query: &Query<&Ground>,
     ground_intersections: &mut GroundIntersections,
     entity: Entity,
     shape: &Collider,
@@ -254,8 +269,7 @@ fn ground_collision(
         .exclude_sensors(),
         |collider_entity| {
             let entity = collider_entity;
-            let (_, collider_friction) = ground_query.get(collider_entity).unwrap();
-            ground_friction = Some(collider_friction.coefficient * friction);
+            ground_friction = Some(friction);
             ground_colliders.insert(entity);
             if !ground_intersections.0.contains(&entity) {
                 timer.reset();
@@ -271,6 +285,7 @@ fn ground_friction_or_gravity(
     ground_friction: Option<f32>,
     vel: &mut PlayerVelocity,
     mprops: &ReadMassProperties,
+    profile: &PhysicsProfile,
 ) {
     if let Some(friction) = ground_friction {
         if vel.0.y < 0. {
@@ -278,22 +293,23 @@ fn ground_friction_or_gravity(
         }
         if vel.0.x.abs() > f32::EPSILON {
             vel.0.x += if vel.0.x > 0. {
-                -GROUND_FRICTION_STATIC_MULTIPLIER * friction
+                -profile.ground_friction_static * friction
             } else {
-                GROUND_FRICTION_STATIC_MULTIPLIER * friction
+                profile.ground_friction_static * friction
             };
-            vel.0.x *= 1.0 / (1.0 + GROUND_FRICTION_KINETIC_MULTIPLIER * friction);
-            if vel.0.x.abs() < GROUND_FRICTION_MIN_VEL {
+            vel.0.x *= 1.0 / (1.0 + profile.ground_friction_kinetic * friction);
+            if vel.0.x.abs() < profile.ground_friction_min_vel {
                 vel.0.x = 0.
             }
         }
     } else {
-        vel.0.y -= RAPIER_GRAVITY * mprops.0.into_rapier(RAPIER_SCALE).inv_mass;
+        vel.0.y -= profile.gravity * mprops.0.into_rapier(RAPIER_SCALE).inv_mass;
     }
 }
 
-pub fn apply_vel(mut query: Query<(&mut Velocity, &PlayerVelocity), With<Player>>) {
-    if let Ok((mut rb_vel, vel)) = query.get_single_mut() {
-        rb_vel.linvel = vel.0;
+/// Moves the player by its velocity, since its rigid body is position based.
+pub fn apply_vel(mut query: Query<(&mut Transform, &PlayerVelocity), With<Player>>) {
+    if let Ok((mut transform, vel)) = query.get_single_mut() {
+        transform.translation += (vel.0 * PLAYER_TIMESTEP).extend(0.);
     }
 }
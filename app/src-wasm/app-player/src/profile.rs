@@ -0,0 +1,198 @@
+use app_config::*;
+use bevy::{
+    asset::{AssetLoader, BoxedFuture, Error, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::TypeUuid,
+};
+use serde::{Deserialize, Serialize};
+
+/// Loaded from the asset tar, or from the assets of the app if the tar does not contain it.
+pub const PHYSICS_PROFILE_PATH: &str = "player.physics.ron";
+
+/// The tunable values of the player movement.
+///
+/// The default is the tuning the game ships with, and missing values in a profile fall back to it.
+#[derive(Clone, Debug, Deserialize, PartialEq, Resource, Serialize, TypeUuid)]
+#[serde(default)]
+#[uuid = "3f0c2a65-5a3e-4d7b-9a41-8de1d5c7b0f2"]
+pub struct PhysicsProfile {
+    pub gravity: f32,
+    pub jump_force: f32,
+    pub high_jump_tick: u8,
+    pub high_jump_tick_walk: u8,
+    /// Horizontal speed above which a jump is held for [`PhysicsProfile::high_jump_tick_walk`].
+    pub high_jump_walk_threshold: f32,
+    pub move_impulse_ground: f32,
+    pub move_impulse_ground_run: f32,
+    pub move_impulse_air: f32,
+    pub move_impulse_air_run: f32,
+    pub linvel_cap_walk: f32,
+    pub linvel_cap_run: f32,
+    pub linvel_cap_stoop: f32,
+    /// Horizontal speed above which the player dashes.
+    pub run_threshold: f32,
+    /// Friction of the ground, which the player friction and the multipliers below scale.
+    pub ground_friction: f32,
+    pub ground_friction_static: f32,
+    pub ground_friction_kinetic: f32,
+    /// Horizontal speed on the ground below which the player stops.
+    pub ground_friction_min_vel: f32,
+    /// Scales the ground friction while stooping faster than [`PhysicsProfile::linvel_cap_stoop`].
+    pub crouch_slide_friction: f32,
+}
+
+impl Default for PhysicsProfile {
+    fn default() -> Self {
+        Self {
+            gravity: RAPIER_GRAVITY,
+            jump_force: JUMP_FORCE,
+            high_jump_tick: HIGH_JUMP_TICK,
+            high_jump_tick_walk: HIGH_JUMP_TICK_WALK,
+            high_jump_walk_threshold: HIGH_JUMP_WALK_THRESHOLD,
+            move_impulse_ground: MOVE_IMPULSE_MULTIPLIER_GROUND,
+            move_impulse_ground_run: MOVE_IMPULSE_MULTIPLIER_GROUND_RUN,
+            move_impulse_air: MOVE_IMPULSE_MULTIPLIER_AIR,
+            move_impulse_air_run: MOVE_IMPULSE_MULTIPLIER_AIR_RUN,
+            linvel_cap_walk: LINVEL_CAP_WALK,
+            linvel_cap_run: LINVEL_CAP_RUN,
+            linvel_cap_stoop: LINVEL_CAP_STOOP,
+            run_threshold: RUN_THRESHOLD,
+            ground_friction: GROUND_FRICTION,
+            ground_friction_static: GROUND_FRICTION_STATIC_MULTIPLIER,
+            ground_friction_kinetic: GROUND_FRICTION_KINETIC_MULTIPLIER,
+            ground_friction_min_vel: GROUND_FRICTION_MIN_VEL,
+            crouch_slide_friction: CROUCH_SLIDE_FRICTION_MULTIPLIER,
+        }
+    }
+}
+
+/// A value of a [`PhysicsProfile`], as edited by the debug panel.
+pub enum PhysicsValueMut<'a> {
+    Float(&'a mut f32),
+    Tick(&'a mut u8),
+}
+
+impl PhysicsValueMut<'_> {
+    /// Changes the value by 10 % per step, or by one per step for ticks.
+    pub fn step(&mut self, steps: i8) {
+        match self {
+            Self::Float(value) => **value *= 1.1f32.powi(steps.into()),
+            Self::Tick(tick) => **tick = tick.saturating_add_signed(steps),
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            Self::Float(value) => format!("{value:.3}"),
+            Self::Tick(tick) => tick.to_string(),
+        }
+    }
+}
+
+impl PhysicsProfile {
+    /// Names and values of all fields, in the order of the debug panel.
+    pub fn fields_mut(&mut self) -> [(&'static str, PhysicsValueMut<'_>); 18] {
+        use PhysicsValueMut::*;
+        [
+            ("gravity", Float(&mut self.gravity)),
+            ("jump_force", Float(&mut self.jump_force)),
+            ("high_jump_tick", Tick(&mut self.high_jump_tick)),
+            ("high_jump_tick_walk", Tick(&mut self.high_jump_tick_walk)),
+            (
+                "high_jump_walk_threshold",
+                Float(&mut self.high_jump_walk_threshold),
+            ),
+            ("move_impulse_ground", Float(&mut self.move_impulse_ground)),
+            (
+                "move_impulse_ground_run",
+                Float(&mut self.move_impulse_ground_run),
+            ),
+            ("move_impulse_air", Float(&mut self.move_impulse_air)),
+            (
+                "move_impulse_air_run",
+                Float(&mut self.move_impulse_air_run),
+            ),
+            ("linvel_cap_walk", Float(&mut self.linvel_cap_walk)),
+            ("linvel_cap_run", Float(&mut self.linvel_cap_run)),
+            ("linvel_cap_stoop", Float(&mut self.linvel_cap_stoop)),
+            ("run_threshold", Float(&mut self.run_threshold)),
+            ("ground_friction", Float(&mut self.ground_friction)),
+            (
+                "ground_friction_static",
+                Float(&mut self.ground_friction_static),
+            ),
+            (
+                "ground_friction_kinetic",
+                Float(&mut self.ground_friction_kinetic),
+            ),
+            (
+                "ground_friction_min_vel",
+                Float(&mut self.ground_friction_min_vel),
+            ),
+            (
+                "crouch_slide_friction",
+                Float(&mut self.crouch_slide_friction),
+            ),
+        ]
+    }
+
+    pub fn from_ron(bytes: &[u8]) -> Result<Self, ron::error::SpannedError> {
+        ron::de::from_bytes(bytes)
+    }
+
+    pub fn to_ron(&self) -> Result<String, ron::Error> {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct PhysicsProfileLoader;
+
+impl AssetLoader for PhysicsProfileLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), Error>> {
+        Box::pin(async move {
+            load_context.set_default_asset(LoadedAsset::new(PhysicsProfile::from_ron(bytes)?));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["physics.ron"]
+    }
+}
+
+/// Keeps the loaded profile alive, so that changes to its file are applied.
+#[derive(Resource)]
+pub(crate) struct PhysicsProfileHandle(Handle<PhysicsProfile>);
+
+pub(crate) fn load_physics_profile(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(PhysicsProfileHandle(
+        asset_server.load(PHYSICS_PROFILE_PATH),
+    ));
+}
+
+/// Replaces the [`PhysicsProfile`] resource with the loaded one.
+///
+/// The default stays in place if the profile cannot be loaded.
+pub(crate) fn apply_physics_profile(
+    mut profile: ResMut<PhysicsProfile>,
+    mut asset_events: EventReader<AssetEvent<PhysicsProfile>>,
+    assets: Res<Assets<PhysicsProfile>>,
+    handle: Res<PhysicsProfileHandle>,
+) {
+    for event in asset_events.iter() {
+        if let AssetEvent::Created { handle: loaded } | AssetEvent::Modified { handle: loaded } =
+            event
+        {
+            if *loaded == handle.0 {
+                if let Some(loaded) = assets.get(loaded) {
+                    *profile = loaded.clone();
+                }
+            }
+        }
+    }
+}
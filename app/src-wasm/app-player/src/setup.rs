@@ -1,6 +1,10 @@
-use crate::{GroundIntersections, Player, PlayerState, PlayerVelocity, WalkAnimationTimer};
+use crate::{
+    stoop::{collider_shape, PlayerCollider},
+    GroundIntersections, PhysicsProfile, Player, PlayerInterpolation, PlayerState, PlayerVelocity,
+    WalkAnimationTimer,
+};
 use app_config::*;
-use app_core::{grid_to_world, PlayerFrame, PlayerSpriteHandles};
+use app_core::{grid_to_world, CourseRes, PlayerFrame, PlayerSpriteHandles};
 use bevy::{prelude::*, sprite::TextureAtlasBuilder};
 use bevy_rapier::prelude::*;
 
@@ -10,14 +14,12 @@ pub fn setup(
     mut textures: ResMut<Assets<Image>>,
     player_sprite_handles: Res<PlayerSpriteHandles>,
     mut texture_atlases: ResMut<Assets<TextureAtlas>>,
+    course: Res<CourseRes>,
+    profile: Res<PhysicsProfile>,
 ) {
-    rapier_config.gravity = Vec2::new(0., -RAPIER_GRAVITY);
+    rapier_config.gravity = Vec2::new(0., -profile.gravity);
 
     let scale_size = 2.;
-    let sprite_size_x = scale_size * 7.0;
-    let sprite_size_y = scale_size * 10.0;
-    let collider_size_x = sprite_size_x * 0.72;
-    let collider_size_y = sprite_size_y * 0.78;
 
     let mut texture_atlas_builder = TextureAtlasBuilder::default();
 
@@ -32,19 +34,19 @@ pub fn setup(
         .unwrap();
     let atlas_handle = texture_atlases.add(texture_atlas);
 
-    let world_pos = grid_to_world(&[5, 6]);
+    let world_pos = grid_to_world(&course.player_start());
 
     commands
         .spawn((
-            RigidBody::KinematicVelocityBased,
+            RigidBody::KinematicPositionBased,
             SpatialBundle {
                 transform: Transform::from_xyz(world_pos.x, world_pos.y, Z_INDEX_PLAYER),
                 visibility: Visibility { is_visible: true },
                 ..default()
             },
             LockedAxes::ROTATION_LOCKED,
-            Velocity::default(),
             PlayerVelocity::default(),
+            PlayerInterpolation::default(),
             ReadMassProperties(MassProperties::from_rapier(
                 bevy_rapier::rapier::prelude::MassProperties::from_ball(100., 100.),
                 RAPIER_SCALE,
@@ -74,11 +76,8 @@ pub fn setup(
         })
         .with_children(|parent| {
             parent.spawn((
-                Collider::round_cuboid(
-                    collider_size_x - PLAYER_COLLIDER_BORDER_RADIUS,
-                    collider_size_y - PLAYER_COLLIDER_BORDER_RADIUS,
-                    PLAYER_COLLIDER_BORDER_RADIUS,
-                ),
+                collider_shape(false),
+                PlayerCollider::default(),
                 Transform::default(),
             ));
         });
@@ -1,48 +1,106 @@
-#![allow(unused_imports)]
-#![allow(unused_variables)]
-
-use crate::Player;
-// use app_config::RAPIER_SCALE;
-use bevy::prelude::*;
-use bevy_rapier::{prelude::*, rapier::math::Isometry};
+use crate::{Player, PlayerFrozen, PlayerInput};
+use app_config::*;
+use app_core::TileCollider;
+use bevy::{math::Vec3Swizzles, prelude::*};
+use bevy_rapier::prelude::*;
 
 pub struct StoopEvent {
     pub is_stooping: bool,
 }
 
-// TODO unsupported by physics engine for now
+/// Marks the collider of the player with the shape it currently has.
+#[derive(Component, Default)]
+pub struct PlayerCollider {
+    is_stooping: bool,
+}
+
+/// The collider of the player, whose bottom stays in place while stooping.
+pub(crate) fn collider_shape(is_stooping: bool) -> Collider {
+    if is_stooping {
+        let shape = Collider::round_cuboid(
+            PLAYER_COLLIDER_HALF_WIDTH - PLAYER_COLLIDER_BORDER_RADIUS,
+            PLAYER_COLLIDER_HALF_HEIGHT_STOOP - PLAYER_COLLIDER_BORDER_RADIUS,
+            PLAYER_COLLIDER_BORDER_RADIUS,
+        );
+        Collider::compound(vec![(
+            Vec2::new(
+                0.,
+                PLAYER_COLLIDER_HALF_HEIGHT_STOOP - PLAYER_COLLIDER_HALF_HEIGHT,
+            ),
+            0.,
+            shape,
+        )])
+    } else {
+        Collider::round_cuboid(
+            PLAYER_COLLIDER_HALF_WIDTH - PLAYER_COLLIDER_BORDER_RADIUS,
+            PLAYER_COLLIDER_HALF_HEIGHT - PLAYER_COLLIDER_BORDER_RADIUS,
+            PLAYER_COLLIDER_BORDER_RADIUS,
+        )
+    }
+}
+
 pub fn stoop(
-    mut query: Query<(&Player, &mut Children)>,
-    // mut child_query: Query<(&mut Collider, &mut Transform)>,
-    keyboard_input: Res<Input<KeyCode>>,
+    query: Query<(&Player, &Transform), Without<PlayerFrozen>>,
+    tile_collider_query: Query<(), With<TileCollider>>,
+    input: Res<PlayerInput>,
     mut stoop_events: EventWriter<StoopEvent>,
-    // mut context: ResMut<RapierContext>,
+    ctx: Res<RapierContext>,
 ) {
-    if let Ok((player, children)) = query.get_single_mut() {
-        // let child = children.get(1).unwrap();
-        // let (mut collider, mut transform) = child_query.get_mut(*child).unwrap();
-        let stooped = !player.state.is_stooping
-            && (keyboard_input.pressed(KeyCode::S) || keyboard_input.pressed(KeyCode::Down));
-        let unstooped = player.state.is_stooping
-            && !keyboard_input.pressed(KeyCode::S)
-            && !keyboard_input.pressed(KeyCode::Down);
+    if let Ok((player, transform)) = query.get_single() {
+        let stooped = !player.state.is_stooping && input.current.down;
+        let unstooped = player.state.is_stooping && !input.current.down;
 
         if stooped {
-            // let shape = collider.raw.make_mut().as_round_cuboid_mut().unwrap();
-            // web_sys::console::log_1(&format!("TRANSFORM {:?}", transform.translation).into());
-            // shape.inner_shape.half_extents.data.0[0][1] = 9.68;
-            // collider.promote_scaled_shape();
-
-            // transform.translation.y = (9.68 - 15.2) / 2.;
             stoop_events.send(StoopEvent { is_stooping: true });
-        } else if unstooped {
-            // let shape = collider.raw.make_mut().as_round_cuboid_mut().unwrap();
-            // web_sys::console::log_1(&format!("TRANSFORM {:?}", transform.translation).into());
-            // shape.inner_shape.half_extents.data.0[0][1] = 15.2;
-            // collider.promote_scaled_shape();
-
-            // transform.translation.y = 0.;
+        } else if unstooped && !is_blocked_above(&ctx, transform, &tile_collider_query) {
             stoop_events.send(StoopEvent { is_stooping: false });
         }
     }
 }
+
+/// Whether a tile is in the way of the head when standing up, e.g. a low ceiling.
+///
+/// Only the space between the stooped and the standing height is checked,
+/// the ground below the player must not count.
+fn is_blocked_above(
+    ctx: &RapierContext,
+    transform: &Transform,
+    tile_collider_query: &Query<(), With<TileCollider>>,
+) -> bool {
+    let head_half_height = PLAYER_COLLIDER_HALF_HEIGHT - PLAYER_COLLIDER_HALF_HEIGHT_STOOP;
+    let mut is_blocked = false;
+    ctx.intersections_with_shape(
+        transform.translation.xy() + Vec2::new(0., PLAYER_COLLIDER_HALF_HEIGHT_STOOP),
+        transform.rotation.to_axis_angle().1,
+        &Collider::cuboid(
+            PLAYER_COLLIDER_HALF_WIDTH - PLAYER_COLLIDER_BORDER_RADIUS,
+            head_half_height,
+        ),
+        QueryFilter {
+            predicate: Some(&|entity| tile_collider_query.contains(entity)),
+            ..Default::default()
+        }
+        .exclude_sensors(),
+        |_| {
+            is_blocked = true;
+            false
+        },
+    );
+    is_blocked
+}
+
+/// Swaps the shape of the collider when the player starts or stops stooping.
+pub fn resize_collider(
+    query: Query<(&Player, &Children)>,
+    mut child_query: Query<(&mut Collider, &mut PlayerCollider)>,
+) {
+    if let Ok((player, children)) = query.get_single() {
+        let child = children.get(1).unwrap();
+        if let Ok((mut collider, mut player_collider)) = child_query.get_mut(*child) {
+            if player_collider.is_stooping != player.state.is_stooping {
+                player_collider.is_stooping = player.state.is_stooping;
+                *collider = collider_shape(player.state.is_stooping);
+            }
+        }
+    }
+}
@@ -1,5 +1,7 @@
 use crate::{Player, PlayerStateEnum, PlayerVelocity};
+use app_config::*;
 use bevy::prelude::*;
+use std::time::Duration;
 
 #[derive(Debug)]
 pub enum WalkEvent {
@@ -13,7 +15,6 @@ pub struct WalkAnimationTimer(pub Timer);
 
 pub fn walk_animation(
     mut query: Query<(&Player, &mut WalkAnimationTimer, &PlayerVelocity)>,
-    time: Res<Time>,
     mut walk_event: EventWriter<WalkEvent>,
 ) {
     for (player, mut timer, vel) in query.iter_mut() {
@@ -23,7 +24,7 @@ pub fn walk_animation(
                 timer.reset();
                 walk_event.send(WalkEvent::Stop);
             } else {
-                timer.tick(time.delta() * linvel as u32);
+                timer.tick(Duration::from_secs_f32(PLAYER_TIMESTEP) * linvel as u32);
                 if timer.finished() {
                     walk_event.send(WalkEvent::Advance);
                 }
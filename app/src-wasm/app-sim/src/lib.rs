@@ -0,0 +1,469 @@
+//! Runs the player simulation of [`PlayerPlugin`] without a window or assets,
+//! so that its behaviour can be tested.
+
+use app_config::*;
+use app_core::{
+    load_player_sprites, AppStage, AppState, CourseRes, GameMode, GameModeToggleEvent, InputReplay,
+    PlayerSpriteHandles,
+};
+use app_player::{PhysicsProfile, Player, PlayerPlugin, PlayerState, PlayerVelocity};
+use bevy::{
+    ecs::system::{CommandQueue, SystemState},
+    hierarchy::HierarchyPlugin,
+    prelude::*,
+    time::FixedTimesteps,
+    transform::TransformPlugin,
+};
+use bevy_rapier::{plugin::RapierPhysicsPlugin, prelude::*};
+use shrm_core::{AreaId, Course, InputFrame, Replay, TileVariant};
+use std::collections::HashMap;
+
+/// The player after a step of the simulation.
+#[derive(Clone, Debug)]
+pub struct PlayerSample {
+    pub position: Vec2,
+    pub velocity: Vec2,
+    pub state: PlayerState,
+}
+
+/// A minimal app with rapier and the [`PlayerPlugin`] that steps the player simulation
+/// once per call to [`PlayerSimulation::step`].
+///
+/// Only the tiles of the main area are spawned, without the start marker and the goal.
+pub struct PlayerSimulation {
+    app: App,
+}
+
+impl PlayerSimulation {
+    pub fn new(course: &Course) -> Self {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugin(TransformPlugin)
+            .add_plugin(HierarchyPlugin)
+            .add_plugin(AssetPlugin::default())
+            .add_asset::<Image>()
+            .add_asset::<TextureAtlas>()
+            // required by the async colliders of rapier
+            .add_asset::<Mesh>()
+            // rapier must not depend on the frame time either
+            .insert_resource(RapierConfiguration {
+                timestep_mode: TimestepMode::Fixed {
+                    dt: PLAYER_TIMESTEP,
+                    substeps: 1,
+                },
+                ..Default::default()
+            })
+            .add_plugin(RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(
+                RAPIER_SCALE,
+            ))
+            // unlike in the app, every update is exactly one step
+            .add_stage_after(
+                CoreStage::Update,
+                AppStage::PlayerStep,
+                SystemStage::parallel(),
+            )
+            .insert_resource(State::new(AppState::Game))
+            .insert_resource(GameMode::Play)
+            .insert_resource(InputReplay::play(Replay {
+                course: course.clone(),
+                frames: vec![],
+            }))
+            .init_resource::<Input<KeyCode>>()
+            .init_resource::<PlayerSpriteHandles>()
+            .init_resource::<FixedTimesteps>()
+            .add_event::<GameModeToggleEvent>()
+            .add_plugin(PlayerPlugin)
+            .add_system_set_to_stage(CoreStage::First, State::<AppState>::get_driver())
+            .add_system_set_to_stage(CoreStage::PreUpdate, State::<AppState>::get_driver())
+            .add_system_set_to_stage(CoreStage::Update, State::<AppState>::get_driver())
+            .add_system_set_to_stage(AppStage::PlayerStep, State::<AppState>::get_driver())
+            .add_system_set_to_stage(CoreStage::PostUpdate, State::<AppState>::get_driver())
+            .add_system_set_to_stage(CoreStage::Last, State::<AppState>::get_driver());
+
+        load_blank_player_sprites(&mut app.world);
+        let course_res = load_course(&mut app.world, course);
+        app.insert_resource(course_res);
+        Self { app }
+    }
+
+    /// Replaces the [`PhysicsProfile`], which is the default otherwise.
+    pub fn set_profile(&mut self, profile: PhysicsProfile) {
+        self.app.insert_resource(profile);
+    }
+
+    /// Spawns a tile into the running simulation, e.g. above the player.
+    pub fn spawn_tile(&mut self, grid_pos: [i32; 2], variant: TileVariant) {
+        let world = &mut self.app.world;
+        world.resource_scope(|world, mut course_res: Mut<CourseRes>| {
+            let mut queue = CommandQueue::default();
+            let mut commands = Commands::new(&mut queue, world);
+            course_res.spawn_tile(&mut commands, &grid_pos, &variant, &mut HashMap::new());
+            queue.apply(world);
+        });
+    }
+
+    /// Runs one step with the given input.
+    pub fn step(&mut self, input: InputFrame) -> PlayerSample {
+        // the input is read from a replay, which plays back the pushed frame in the step
+        self.app
+            .world
+            .resource_mut::<InputReplay>()
+            .frames
+            .push(input);
+        self.app.update();
+        self.sample()
+    }
+
+    /// Runs one step per input and returns the trajectory of the player.
+    pub fn run(&mut self, inputs: impl IntoIterator<Item = InputFrame>) -> Vec<PlayerSample> {
+        inputs.into_iter().map(|input| self.step(input)).collect()
+    }
+
+    /// Steps without input until the player stands on the ground.
+    ///
+    /// Returns whether it did within `max_steps`.
+    pub fn settle(&mut self, max_steps: usize) -> bool {
+        (0..max_steps).any(|_| self.step(InputFrame::default()).state.is_grounded())
+    }
+
+    pub fn sample(&mut self) -> PlayerSample {
+        let (transform, vel, player) = self
+            .app
+            .world
+            .query::<(&Transform, &PlayerVelocity, &Player)>()
+            .single(&self.app.world);
+        PlayerSample {
+            position: transform.translation.truncate(),
+            velocity: vel.0,
+            state: player.state.clone(),
+        }
+    }
+}
+
+/// The sprite files do not exist here, but the player needs a texture atlas of them.
+fn load_blank_player_sprites(world: &mut World) {
+    let mut state: SystemState<(ResMut<PlayerSpriteHandles>, Res<AssetServer>)> =
+        SystemState::new(world);
+    let (sprite_handles, asset_server) = state.get_mut(world);
+    load_player_sprites(sprite_handles, asset_server);
+    let ids: Vec<_> = world
+        .resource::<PlayerSpriteHandles>()
+        .0
+        .values()
+        .map(|handle| handle.id())
+        .collect();
+    let mut images = world.resource_mut::<Assets<Image>>();
+    for id in ids {
+        images.set_untracked(id, Image::default());
+    }
+}
+
+fn load_course(world: &mut World, course: &Course) -> CourseRes {
+    let mut course_res = CourseRes {
+        texture_atlas_handle: Handle::default(),
+        texture_atlas_handle_transparent: Handle::default(),
+        tiles: HashMap::default(),
+        theme: course.main_area.theme.clone(),
+        area: AreaId::Main,
+        width: course.main_area.width,
+        height: course.main_area.height,
+        unloaded_areas: HashMap::default(),
+        links: course.links.clone(),
+        start_pos: course.start_pos,
+        goal_pos_x: course.goal_pos_x,
+        metadata: course.metadata.clone(),
+    };
+    let mut queue = CommandQueue::default();
+    let mut commands = Commands::new(&mut queue, world);
+    let mut events = HashMap::new();
+    for (grid_pos, tile) in course.main_area.tiles.iter() {
+        course_res.spawn_tile(&mut commands, grid_pos, tile, &mut events);
+    }
+    queue.apply(world);
+    course_res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use app_core::world_to_grid_pos;
+    use app_player::PhysicsValueMut;
+    use shrm_core::{CourseArea, CourseMetadata, GroundVariant, ThemeVariant};
+
+    const MAX_STEPS: usize = 600;
+
+    fn flat_course() -> Course {
+        let mut main_area = CourseArea::new(ThemeVariant::Plain);
+        for x in 0..main_area.width {
+            for y in 0..FIXED_GROUND_HEIGHT {
+                main_area
+                    .tiles
+                    .insert([x, y], TileVariant::Ground(GroundVariant::default()));
+            }
+        }
+        Course {
+            main_area,
+            sub_area: CourseArea::new(ThemeVariant::Underground),
+            links: vec![],
+            start_pos: [0, 0],
+            goal_pos_x: 120,
+            metadata: CourseMetadata::default(),
+        }
+    }
+
+    fn settled() -> PlayerSimulation {
+        let mut sim = PlayerSimulation::new(&flat_course());
+        assert!(sim.settle(MAX_STEPS));
+        sim
+    }
+
+    fn input(right: bool, run: bool, jump: bool) -> InputFrame {
+        InputFrame {
+            right,
+            run,
+            jump,
+            ..Default::default()
+        }
+    }
+
+    fn stoop() -> InputFrame {
+        InputFrame {
+            down: true,
+            ..Default::default()
+        }
+    }
+
+    fn max_speed(trajectory: &[PlayerSample]) -> f32 {
+        trajectory
+            .iter()
+            .map(|sample| sample.velocity.x)
+            .fold(0., f32::max)
+    }
+
+    /// The largest change of the speed within a single step.
+    fn max_impulse(trajectory: &[PlayerSample]) -> f32 {
+        trajectory
+            .windows(2)
+            .map(|samples| samples[1].velocity.x - samples[0].velocity.x)
+            .fold(0., f32::max)
+    }
+
+    fn apex(trajectory: &[PlayerSample], ground_y: f32) -> f32 {
+        trajectory
+            .iter()
+            .map(|sample| sample.position.y - ground_y)
+            .fold(0., f32::max)
+    }
+
+    /// Moves right until the speed no longer changes and returns the trajectory.
+    fn accelerate(sim: &mut PlayerSimulation, run: bool) -> Vec<PlayerSample> {
+        sim.run((0..MAX_STEPS / 2).map(|_| input(true, run, false)))
+    }
+
+    /// Releases all input after accelerating and returns the distance until the player stopped.
+    fn stop_distance(run: bool) -> f32 {
+        stop_distance_with(PhysicsProfile::default(), run)
+    }
+
+    fn stop_distance_with(profile: PhysicsProfile, run: bool) -> f32 {
+        let mut sim = settled();
+        sim.set_profile(profile);
+        accelerate(&mut sim, run);
+        let start = sim.sample().position.x;
+        let trajectory = sim.run((0..MAX_STEPS).map(|_| InputFrame::default()));
+        let stopped = trajectory
+            .iter()
+            .find(|sample| sample.velocity.x == 0.)
+            .expect("player did not stop");
+        stopped.position.x - start
+    }
+
+    /// Jumps after `walk_steps` of walking, holding jump for `jump_steps`, and returns the apex.
+    fn jump_height(walk_steps: usize, jump_steps: usize) -> f32 {
+        jump_height_with(PhysicsProfile::default(), walk_steps, jump_steps)
+    }
+
+    fn jump_height_with(profile: PhysicsProfile, walk_steps: usize, jump_steps: usize) -> f32 {
+        let mut sim = settled();
+        sim.set_profile(profile);
+        sim.run((0..walk_steps).map(|_| input(true, false, false)));
+        let ground_y = sim.sample().position.y;
+        let trajectory =
+            sim.run((0..MAX_STEPS / 2).map(|step| input(walk_steps > 0, false, step < jump_steps)));
+        apex(&trajectory, ground_y)
+    }
+
+    #[test]
+    fn walk_speed_is_capped() {
+        let mut sim = settled();
+        let trajectory = accelerate(&mut sim, false);
+        let last = trajectory.last().unwrap();
+        assert!(max_speed(&trajectory) <= LINVEL_CAP_WALK + max_impulse(&trajectory));
+        assert!(last.velocity.x > HIGH_JUMP_WALK_THRESHOLD);
+        assert!(!last.state.is_dashing());
+    }
+
+    #[test]
+    fn run_speed_is_capped() {
+        let mut sim = settled();
+        let trajectory = accelerate(&mut sim, true);
+        let last = trajectory.last().unwrap();
+        assert!(max_speed(&trajectory) <= LINVEL_CAP_RUN + max_impulse(&trajectory));
+        assert!(last.velocity.x > LINVEL_CAP_WALK);
+        assert!(last.state.is_dashing());
+    }
+
+    #[test]
+    fn held_jump_is_higher_when_walking() {
+        let tapped = jump_height(0, 1);
+        let standing = jump_height(0, MAX_STEPS);
+        let walking = jump_height(MAX_STEPS / 10, MAX_STEPS);
+        assert!(tapped < standing);
+        assert!(standing < walking);
+        // both jumps only differ in the steps in which the jump force is held
+        let extra_ticks = (HIGH_JUMP_TICK_WALK - HIGH_JUMP_TICK) as f32;
+        assert!(walking - standing <= extra_ticks * JUMP_FORCE * PLAYER_TIMESTEP);
+        assert!(walking - standing >= extra_ticks * JUMP_FORCE * PLAYER_TIMESTEP / 2.);
+    }
+
+    #[test]
+    fn friction_stops_the_player() {
+        let walking = stop_distance(false);
+        let running = stop_distance(true);
+        assert!(walking > 0.);
+        assert!(running > walking);
+    }
+
+    #[test]
+    fn jump_lands_on_the_ground() {
+        let mut sim = settled();
+        let ground_y = sim.sample().position.y;
+        let trajectory = sim.run((0..MAX_STEPS / 2).map(|step| input(false, false, step < 5)));
+        let take_off = trajectory
+            .iter()
+            .position(|sample| !sample.state.is_grounded())
+            .expect("player did not jump");
+        let fall = take_off
+            + trajectory[take_off..]
+                .iter()
+                .position(|sample| sample.velocity.y < 0.)
+                .expect("player did not fall");
+        let landing = fall
+            + trajectory[fall..]
+                .iter()
+                .position(|sample| sample.state.is_grounded())
+                .expect("player did not land");
+        let last = trajectory.last().unwrap();
+        assert!(trajectory[take_off..fall]
+            .iter()
+            .all(|sample| !sample.state.is_grounded() && sample.velocity.y >= 0.));
+        assert!(trajectory[landing..]
+            .iter()
+            .all(|sample| sample.state.is_grounded()));
+        assert!((last.position.y - ground_y).abs() < 1.);
+        assert_eq!(last.velocity.y, 0.);
+    }
+
+    #[test]
+    fn crouch_slide_is_longer_than_the_stop() {
+        let mut sim = settled();
+        accelerate(&mut sim, true);
+        let start = sim.sample().position.x;
+        let trajectory = sim.run((0..MAX_STEPS).map(|_| stoop()));
+        assert!(trajectory.iter().all(|sample| sample.state.is_stooping()));
+        let stopped = trajectory
+            .iter()
+            .find(|sample| sample.velocity.x == 0.)
+            .expect("player did not stop");
+        assert!(stopped.position.x - start > stop_distance(true));
+    }
+
+    /// Stoops, optionally spawns a ceiling one tile above the ground, and releases down.
+    fn stand_up(with_ceiling: bool) -> (f32, Vec<PlayerSample>) {
+        let mut sim = settled();
+        let ground_y = sim.sample().position.y;
+        let stooping = sim.run((0..10).map(|_| stoop()));
+        assert!(stooping.last().unwrap().state.is_stooping());
+        if with_ceiling {
+            let player_x = world_to_grid_pos(sim.sample().position.x);
+            for x in player_x - 1..=player_x + 1 {
+                sim.spawn_tile([x, FIXED_GROUND_HEIGHT + 1], TileVariant::HardBlock);
+            }
+            sim.step(stoop());
+        }
+        let trajectory = sim.run((0..10).map(|_| InputFrame::default()));
+        (ground_y, trajectory)
+    }
+
+    #[test]
+    fn player_stands_up_under_open_sky() {
+        let (ground_y, trajectory) = stand_up(false);
+        assert!(!trajectory[0].state.is_stooping());
+        let last = trajectory.last().unwrap();
+        assert!(!last.state.is_stooping());
+        assert!((last.position.y - ground_y).abs() < 1.);
+    }
+
+    #[test]
+    fn player_stays_stooped_under_a_low_ceiling() {
+        let (ground_y, trajectory) = stand_up(true);
+        assert!(trajectory.iter().all(|sample| sample.state.is_stooping()));
+        let last = trajectory.last().unwrap();
+        assert!((last.position.y - ground_y).abs() < 1.);
+    }
+
+    #[test]
+    fn shipped_profile_is_the_default() {
+        let shipped = include_bytes!("../../../assets/player.physics.ron");
+        let mut shipped = PhysicsProfile::from_ron(shipped).unwrap();
+        let mut default = PhysicsProfile::default();
+        // the file is written by hand, so the floats are rounded
+        for ((name, shipped), (_, default)) in
+            shipped.fields_mut().into_iter().zip(default.fields_mut())
+        {
+            match (shipped, default) {
+                (PhysicsValueMut::Float(shipped), PhysicsValueMut::Float(default)) => {
+                    assert!(
+                        (*shipped - *default).abs() <= default.abs() * 1e-5,
+                        "{name}"
+                    );
+                }
+                (PhysicsValueMut::Tick(shipped), PhysicsValueMut::Tick(default)) => {
+                    assert_eq!(shipped, default, "{name}");
+                }
+                _ => unreachable!(),
+            }
+        }
+    }
+
+    #[test]
+    fn jump_uses_the_profile() {
+        let profile = PhysicsProfile {
+            jump_force: JUMP_FORCE * 1.5,
+            ..Default::default()
+        };
+        assert!(jump_height_with(profile, 0, MAX_STEPS) > jump_height(0, MAX_STEPS));
+    }
+
+    #[test]
+    fn ground_friction_uses_the_profile() {
+        let profile = PhysicsProfile {
+            ground_friction: GROUND_FRICTION * 2.,
+            ..Default::default()
+        };
+        assert!(stop_distance_with(profile, false) < stop_distance(false));
+    }
+
+    #[test]
+    fn same_input_gives_same_trajectory() {
+        let inputs: Vec<_> = (0..MAX_STEPS)
+            .map(|step| input(step % 90 < 60, step % 200 > 100, step % 45 < 20))
+            .collect();
+        let first = settled().run(inputs.clone());
+        let second = settled().run(inputs);
+        for (a, b) in first.iter().zip(second.iter()) {
+            assert_eq!(a.position, b.position);
+            assert_eq!(a.velocity, b.velocity);
+        }
+    }
+}
@@ -1,23 +1,37 @@
 mod preview;
+mod selection;
 
 use app_core::{
-    cursor_to_world, world_to_grid, AppState, CourseRes, DespawnTileEvent, Dragging, GameMode,
-    GroundTileUpdateEvent, MainCameraQuery, SelectedTile, SpawnTileEvent,
+    cursor_to_world, world_to_grid, AppState, CourseRes, DespawnTileEvent, Dragging, EditorTool,
+    GameMode, GroundTileUpdateEvent, MainCameraQuery, SelectedTile, SpawnTileEvent, ToolStart,
 };
 use bevy::prelude::*;
+use selection::{
+    put_down_before_leaving, select, update_selection_visuals, update_tiles_lifted, Selection,
+};
+use shrm_core::{line, TileVariant};
 
 pub struct TilePlugin;
 
 impl Plugin for TilePlugin {
     fn build(&self, app: &mut App) {
-        app.add_event::<SpawnTileEvent>()
+        app.init_resource::<Selection>()
+            .add_event::<SpawnTileEvent>()
             .add_event::<DespawnTileEvent>()
             .add_event::<GroundTileUpdateEvent>()
+            // after the menu sent play mode and area switch events, but before they are handled
+            .add_system_set_to_stage(
+                CoreStage::PreUpdate,
+                SystemSet::on_update(AppState::Game).with_system(put_down_before_leaving),
+            )
             .add_system_set_to_stage(
                 CoreStage::PostUpdate,
                 SystemSet::on_update(AppState::Game)
                     .with_system(spawn_tile)
-                    .with_system(preview::spawn_tile_preview),
+                    .with_system(preview::spawn_tile_preview)
+                    .with_system(select)
+                    .with_system(update_selection_visuals.after(select))
+                    .with_system(update_tiles_lifted.after(select)),
             );
     }
 }
@@ -27,16 +41,39 @@ fn spawn_tile(
     mouse_button_input: Res<Input<MouseButton>>,
     windows: Res<Windows>,
     camera_query: MainCameraQuery,
-    button_query: Query<&Interaction, With<Button>>,
-    spawn_tile_events: EventWriter<SpawnTileEvent>,
+    interaction_query: Query<&Interaction>,
+    mut spawn_tile_events: EventWriter<SpawnTileEvent>,
     despawn_tile_events: EventWriter<DespawnTileEvent>,
     course: Res<CourseRes>,
     game_mode: Res<GameMode>,
     selected_tile: Res<SelectedTile>,
     dragging: Res<Dragging>,
+    tool: Res<EditorTool>,
+    mut tool_start: ResMut<ToolStart>,
+    mut brush_pos: Local<Option<[i32; 2]>>,
 ) {
+    // only continues the stroke if the brush was used on the previous frame
+    let last_brush_pos = brush_pos.take();
     if let GameMode::Build { is_editing: true } = *game_mode {
-        for interaction in button_query.iter() {
+        let window = windows.get_primary().unwrap();
+        let cursor_grid_pos = window.cursor_position().map(|cursor_position| {
+            world_to_grid(&cursor_to_world(cursor_position, &camera_query, window))
+        });
+
+        // a line or rectangle is placed once the button is released
+        if mouse_button_input.just_released(MouseButton::Left) {
+            if let (Some(start), Some(grid_pos), Some(selected_tile)) =
+                (tool_start.0.take(), cursor_grid_pos, &selected_tile.0)
+            {
+                send_spawn_tiles(
+                    &mut spawn_tile_events,
+                    tool.cells(&course, Some(start), grid_pos),
+                    selected_tile,
+                );
+            }
+        }
+
+        for interaction in interaction_query.iter() {
             if interaction == &Interaction::Hovered || interaction == &Interaction::Clicked {
                 return;
             }
@@ -44,65 +81,64 @@ fn spawn_tile(
         if dragging.0.is_some() {
             return;
         }
-        let window = windows.get_primary().unwrap();
-        if mouse_button_input.pressed(MouseButton::Left) {
-            send_spawn_tile(
-                window,
-                &camera_query,
-                spawn_tile_events,
-                &course,
-                &selected_tile,
-            );
+        let (grid_pos, selected_tile) =
+            if let (Some(grid_pos), Some(selected_tile)) = (cursor_grid_pos, &selected_tile.0) {
+                (grid_pos, selected_tile)
+            } else {
+                return;
+            };
+        match *tool {
+            EditorTool::Pencil if mouse_button_input.pressed(MouseButton::Left) => {
+                send_spawn_tiles(
+                    &mut spawn_tile_events,
+                    tool.cells(&course, last_brush_pos, grid_pos),
+                    selected_tile,
+                );
+                *brush_pos = Some(grid_pos);
+            }
+            EditorTool::Fill if mouse_button_input.just_pressed(MouseButton::Left) => {
+                send_spawn_tiles(
+                    &mut spawn_tile_events,
+                    tool.cells(&course, None, grid_pos),
+                    selected_tile,
+                );
+            }
+            tool if tool.is_shape() && mouse_button_input.just_pressed(MouseButton::Left) => {
+                tool_start.0 = Some(grid_pos);
+            }
+            _ => {}
         }
         if mouse_button_input.pressed(MouseButton::Right) {
-            send_despawn_tile(window, &camera_query, despawn_tile_events, &course);
+            let cells = line(last_brush_pos.unwrap_or(grid_pos), grid_pos);
+            send_despawn_tiles(cells, despawn_tile_events, &course);
+            *brush_pos = Some(grid_pos);
         }
     }
 }
 
-fn send_spawn_tile(
-    window: &Window,
-    camera_query: &MainCameraQuery,
-    mut spawn_tile_events: EventWriter<SpawnTileEvent>,
-    course: &CourseRes,
-    selected_tile: &SelectedTile,
+fn send_spawn_tiles(
+    spawn_tile_events: &mut EventWriter<SpawnTileEvent>,
+    cells: Vec<[i32; 2]>,
+    tile_variant: &TileVariant,
 ) {
-    let cursor_position = if let Some(cursor_pointer) = window.cursor_position() {
-        cursor_pointer
-    } else {
-        return;
-    };
-
-    let world_pos = cursor_to_world(cursor_position, camera_query, window);
-    let grid_pos = world_to_grid(&world_pos);
-    if let Some(selected_tile) = &selected_tile.0 {
-        if !course.tiles.contains_key(&grid_pos) {
-            spawn_tile_events.send(SpawnTileEvent {
-                tile_variant: selected_tile.clone(),
-                grid_pos,
-            });
-        }
-    }
+    spawn_tile_events.send_batch(cells.into_iter().map(|grid_pos| SpawnTileEvent {
+        tile_variant: tile_variant.clone(),
+        grid_pos,
+    }));
 }
 
-fn send_despawn_tile(
-    window: &Window,
-    camera_query: &MainCameraQuery,
+fn send_despawn_tiles(
+    cells: Vec<[i32; 2]>,
     mut despawn_tile_events: EventWriter<DespawnTileEvent>,
     course: &CourseRes,
 ) {
-    let cursor_position = if let Some(cursor_pointer) = window.cursor_position() {
-        cursor_pointer
-    } else {
-        return;
-    };
-
-    let world_pos = cursor_to_world(cursor_position, camera_query, window);
-    let grid_pos = world_to_grid(&world_pos);
-    if course.tiles.contains_key(&grid_pos) {
-        despawn_tile_events.send(DespawnTileEvent {
-            grid_pos,
-            ..Default::default()
-        });
-    }
+    despawn_tile_events.send_batch(
+        cells
+            .into_iter()
+            .filter(|grid_pos| course.tiles.contains_key(grid_pos))
+            .map(|grid_pos| DespawnTileEvent {
+                grid_pos,
+                ..Default::default()
+            }),
+    );
 }
@@ -1,111 +1,124 @@
 use app_config::*;
 use app_core::{
-    cursor_to_world, grid_to_world, world_to_grid, CourseRes, GameMode, GroundSurroundingMatrix,
-    MainCameraQuery, SelectedTile, TilePlacePreview, TilePreview,
+    cursor_to_world, grid_to_world, world_to_grid, CourseRes, EditorTool, GameMode,
+    MainCameraQuery, SelectedTile, TilePlacePreview, TilePreview, TileSprite, ToolStart,
 };
-use bevy::{prelude::*, render::primitives::Frustum};
-use bevy_rapier::prelude::*;
-use shrm_core::{GroundVariant, TileVariant};
+use bevy::prelude::*;
+use shrm_core::preview_placement;
 
+/// Shows the tiles the selected tool would place, as transparent sprites.
+///
+/// Existing ground tiles that would be autotiled differently show their new variant
+/// until the preview changes. `CourseRes` itself is left untouched.
 #[allow(clippy::too_many_arguments)]
 #[allow(clippy::type_complexity)]
 pub(crate) fn spawn_tile_preview(
-    mut cursor_events: EventReader<CursorMoved>,
     windows: Res<Windows>,
     camera_query: MainCameraQuery,
+    child_query: Query<&Children>,
+    mut sprite_query: Query<&mut TextureAtlasSprite, With<TileSprite>>,
     course: Res<CourseRes>,
     game_mode: Res<GameMode>,
     mut commands: Commands,
     selected_tile: Res<SelectedTile>,
+    tool: Res<EditorTool>,
+    tool_start: Res<ToolStart>,
     mut tile_place_preview: ResMut<TilePlacePreview>,
-    mut query: Query<
-        (&mut Transform, &mut TextureAtlasSprite),
-        (With<TilePreview>, Without<Frustum>),
-    >,
+    mut last_preview: Local<Option<([i32; 2], Option<[i32; 2]>)>>,
+    mut retiled: Local<Vec<[i32; 2]>>,
 ) {
-    if let Some(tile_variant) = &selected_tile.0 {
-        if let GameMode::Build { is_editing: true } = *game_mode {
-            let window = windows.get_primary().unwrap();
-            for cursor_moved in cursor_events.iter() {
-                let world_pos = cursor_to_world(cursor_moved.position, &camera_query, window);
-                let grid_pos = world_to_grid(&world_pos);
+    let window = windows.get_primary().unwrap();
+    let grid_pos = window.cursor_position().map(|cursor_position| {
+        world_to_grid(&cursor_to_world(cursor_position, &camera_query, window))
+    });
+    let is_editing = matches!(*game_mode, GameMode::Build { is_editing: true });
+    let (tile_variant, grid_pos) = match (&selected_tile.0, grid_pos) {
+        (Some(tile_variant), Some(grid_pos)) if is_editing => (tile_variant, grid_pos),
+        _ => {
+            reset_retiled(&mut retiled, &course, &child_query, &mut sprite_query);
+            if let Some((entity, _)) = tile_place_preview.0.take() {
+                commands.entity(entity).despawn_recursive();
+            }
+            *last_preview = None;
+            return;
+        }
+    };
 
-                if grid_pos[0] < 0
-                    || grid_pos[1] < 0
-                    || grid_pos[0] >= MAX_COURSE_X
-                    || grid_pos[1] >= MAX_COURSE_Y
-                {
-                    if let Some((entity, _)) = tile_place_preview.0 {
-                        commands.entity(entity).despawn_recursive();
-                        tile_place_preview.0 = None;
-                    }
-                } else if course.tiles.get(&grid_pos).is_none() {
-                    let world_pos = grid_to_world(&grid_pos);
-                    let surrounding_matrix: Option<GroundSurroundingMatrix> =
-                        if let TileVariant::Ground(_) = tile_variant {
-                            // let surrounding_matrix =
-                            //     get_surrounding_matrix(&grid_pos, &mut course.tiles);
-                            // Some(GroundSurroundingMatrix(surrounding_matrix))
-                            // TODO tile preview not working anyway
-                            None
-                        } else {
-                            None
-                        };
-                    if let Some((entity, tile_pos)) = &mut tile_place_preview.0 {
-                        if *tile_pos != grid_pos {
-                            if let Ok(mut transform) = query.get_component_mut::<Transform>(*entity)
-                            {
-                                transform.translation.x = world_pos.x;
-                                transform.translation.y = world_pos.y;
-                                *tile_pos = grid_pos;
-                                if let Some(surrounding_matrix) = surrounding_matrix {
-                                    let sprite = TextureAtlasSprite::new(
-                                        GroundVariant::from_surrounding_matrix(
-                                            &surrounding_matrix.0,
-                                        )
-                                        .get_sprite_sheet_index(),
-                                    );
-                                    let mut texture_atlas_sprite = query
-                                        .get_component_mut::<TextureAtlasSprite>(*entity)
-                                        .unwrap();
-                                    *texture_atlas_sprite = sprite;
-                                }
-                            }
-                        }
-                    } else {
-                        let sprite = if let Some(surrounding_matrix) = &surrounding_matrix {
-                            TextureAtlasSprite::new(
-                                GroundVariant::from_surrounding_matrix(&surrounding_matrix.0)
-                                    .get_sprite_sheet_index(),
-                            )
-                        } else {
-                            TextureAtlasSprite::new(tile_variant.get_sprite_sheet_index())
-                        };
-                        let entity = commands
-                            .spawn((
-                                RigidBody::Fixed,
-                                SpriteSheetBundle {
-                                    transform: Transform {
-                                        translation: Vec3::new(
-                                            world_pos.x,
-                                            world_pos.y,
-                                            Z_INDEX_TILE,
-                                        ),
-                                        scale: Vec3::new(TILE_SIZE, TILE_SIZE, 0.),
-                                        ..Default::default()
-                                    },
-                                    texture_atlas: course.texture_atlas_handle_transparent.clone(),
-                                    sprite,
-                                    ..Default::default()
-                                },
-                                TilePreview,
-                            ))
-                            .id();
-                        tile_place_preview.0 = Some((entity, grid_pos));
-                    }
-                } else if let Some((entity, _)) = tile_place_preview.0 {
-                    commands.entity(entity).despawn_recursive();
-                    tile_place_preview.0 = None;
+    // the flood fill can get expensive, so only update what actually changed
+    let preview = Some((grid_pos, tool_start.0));
+    let is_unchanged = *last_preview == preview
+        && !course.is_changed()
+        && !selected_tile.is_changed()
+        && !game_mode.is_changed()
+        && !tool.is_changed();
+    if is_unchanged {
+        return;
+    }
+    *last_preview = preview;
+
+    reset_retiled(&mut retiled, &course, &child_query, &mut sprite_query);
+    if let Some((entity, _)) = tile_place_preview.0.take() {
+        commands.entity(entity).despawn_recursive();
+    }
+    let cells = tool.cells(&course, tool_start.0, grid_pos);
+    if cells.is_empty() {
+        return;
+    }
+
+    let preview = preview_placement(&course.tiles, &cells, tile_variant);
+    let entity = commands
+        .spawn((SpatialBundle::default(), TilePreview))
+        .with_children(|parent| {
+            for (cell, variant) in preview.iter() {
+                let index = variant.get_sprite_sheet_index();
+                if let Some(tile) = course.tiles.get(cell) {
+                    set_tile_sprite(tile.entity, index, &child_query, &mut sprite_query);
+                    retiled.push(*cell);
+                    continue;
+                }
+                let world_pos = grid_to_world(cell);
+                parent.spawn(SpriteSheetBundle {
+                    transform: Transform {
+                        translation: Vec3::new(world_pos.x, world_pos.y, Z_INDEX_TILE),
+                        scale: Vec3::new(TILE_SIZE, TILE_SIZE, 0.),
+                        ..Default::default()
+                    },
+                    texture_atlas: course.texture_atlas_handle_transparent.clone(),
+                    sprite: TextureAtlasSprite::new(index),
+                    ..Default::default()
+                });
+            }
+        })
+        .id();
+    tile_place_preview.0 = Some((entity, grid_pos));
+}
+
+/// Shows the actual variants of the tiles that were retiled by the preview again.
+fn reset_retiled(
+    retiled: &mut Vec<[i32; 2]>,
+    course: &CourseRes,
+    child_query: &Query<&Children>,
+    sprite_query: &mut Query<&mut TextureAtlasSprite, With<TileSprite>>,
+) {
+    for grid_pos in retiled.drain(..) {
+        if let Some(tile) = course.tiles.get(&grid_pos) {
+            let index = tile.variant.get_sprite_sheet_index();
+            set_tile_sprite(tile.entity, index, child_query, sprite_query);
+        }
+    }
+}
+
+fn set_tile_sprite(
+    entity: Entity,
+    index: usize,
+    child_query: &Query<&Children>,
+    sprite_query: &mut Query<&mut TextureAtlasSprite, With<TileSprite>>,
+) {
+    if let Ok(children) = child_query.get(entity) {
+        for child in children.iter() {
+            if let Ok(mut sprite) = sprite_query.get_mut(*child) {
+                if sprite.index != index {
+                    sprite.index = index;
                 }
             }
         }
@@ -0,0 +1,573 @@
+use app_config::*;
+use app_core::{
+    cursor_to_world, grid_to_world, grid_to_world_f32, world_to_grid, CourseRes, DespawnTileEvent,
+    Dragging, EditCommand, EditHistory, EditorTool, GameMode, GroundTileUpdateEvent,
+    MainCameraQuery, PlayModeEvent, SpawnTileEvent, SwitchAreaEvent, TileNotEditable, TilesLifted,
+};
+use bevy::prelude::*;
+use shrm_core::TileVariant;
+use std::collections::HashMap;
+
+#[derive(Default, Resource)]
+pub(crate) struct Selection {
+    /// The corner the selection was started at and the opposite one.
+    corners: Option<[[i32; 2]; 2]>,
+    is_selecting: bool,
+    floating: Option<FloatingTiles>,
+    /// Tiles relative to the bottom left corner of the copied region.
+    clipboard: HashMap<[i32; 2], TileVariant>,
+}
+
+/// Tiles that were lifted off the course or pasted, but not yet placed.
+struct FloatingTiles {
+    /// Tiles relative to `pos`.
+    tiles: HashMap<[i32; 2], TileVariant>,
+    pos: [i32; 2],
+    /// Grid offset of the cursor from `pos`.
+    grab: [i32; 2],
+    /// Where lifted tiles were taken from, `None` for pasted ones.
+    origin: Option<[i32; 2]>,
+    /// Dragged tiles are placed once the button is released, pasted ones on the next click.
+    is_dragged: bool,
+}
+
+#[derive(Component)]
+pub(crate) struct SelectionVisual;
+
+/// What the visuals of a selection depend on.
+#[derive(Default, PartialEq)]
+pub(crate) struct SelectionVisualKey {
+    bounds: Option<([i32; 2], [i32; 2])>,
+    floating: Option<([i32; 2], usize)>,
+    is_blocked: bool,
+}
+
+impl Selection {
+    fn bounds(&self) -> Option<([i32; 2], [i32; 2])> {
+        self.corners.map(|[a, b]| {
+            (
+                [a[0].min(b[0]), a[1].min(b[1])],
+                [a[0].max(b[0]), a[1].max(b[1])],
+            )
+        })
+    }
+
+    fn visual_key(&self, course: &CourseRes) -> SelectionVisualKey {
+        SelectionVisualKey {
+            is_blocked: self.is_blocked(course),
+            bounds: self.bounds(),
+            floating: self
+                .floating
+                .as_ref()
+                .map(|floating| (floating.pos, floating.tiles.len())),
+        }
+    }
+
+    fn contains(&self, [x, y]: [i32; 2]) -> bool {
+        self.bounds().is_some_and(|(min, max)| {
+            (min[0]..=max[0]).contains(&x) && (min[1]..=max[1]).contains(&y)
+        })
+    }
+
+    /// Selected tiles that may be edited, relative to the bottom left corner.
+    fn editable_tiles(
+        &self,
+        course: &CourseRes,
+        not_editable_query: &Query<(), With<TileNotEditable>>,
+    ) -> HashMap<[i32; 2], TileVariant> {
+        let (min, _) = if let Some(bounds) = self.bounds() {
+            bounds
+        } else {
+            return HashMap::new();
+        };
+        course
+            .tiles
+            .iter()
+            .filter(|(grid_pos, tile)| {
+                self.contains(**grid_pos) && not_editable_query.get(tile.entity).is_err()
+            })
+            .map(|([x, y], tile)| ([x - min[0], y - min[1]], tile.variant.clone()))
+            .collect()
+    }
+
+    fn float(
+        &mut self,
+        tiles: HashMap<[i32; 2], TileVariant>,
+        pos: [i32; 2],
+        grab: [i32; 2],
+        origin: Option<[i32; 2]>,
+    ) {
+        let max = tiles.keys().fold([0, 0], |[max_x, max_y], [x, y]| {
+            [max_x.max(*x), max_y.max(*y)]
+        });
+        self.corners = Some([pos, [pos[0] + max[0], pos[1] + max[1]]]);
+        self.floating = Some(FloatingTiles {
+            tiles,
+            pos,
+            grab,
+            origin,
+            is_dragged: origin.is_some(),
+        });
+    }
+
+    fn move_floating(&mut self, cursor: [i32; 2]) {
+        if let Some(floating) = &self.floating {
+            self.move_floating_to([cursor[0] - floating.grab[0], cursor[1] - floating.grab[1]]);
+        }
+    }
+
+    fn move_floating_to(&mut self, pos: [i32; 2]) {
+        if let Some(floating) = &mut self.floating {
+            if pos == floating.pos {
+                return;
+            }
+            let [dx, dy] = [pos[0] - floating.pos[0], pos[1] - floating.pos[1]];
+            floating.pos = pos;
+            if let Some([a, b]) = &mut self.corners {
+                *a = [a[0] + dx, a[1] + dy];
+                *b = [b[0] + dx, b[1] + dy];
+            }
+        }
+    }
+
+    /// Whether a floating tile covers a cell that is already taken or outside of the area.
+    fn is_blocked(&self, course: &CourseRes) -> bool {
+        self.floating.as_ref().is_some_and(|floating| {
+            floating
+                .tiles
+                .keys()
+                .any(|[x, y]| !course.is_free(&[floating.pos[0] + x, floating.pos[1] + y]))
+        })
+    }
+
+    /// Takes the floating tiles with the grid positions they are placed at.
+    ///
+    /// The tiles keep floating if they are blocked, so that no tile gets lost.
+    fn take_floating(&mut self, course: &CourseRes) -> Option<Vec<SpawnTileEvent>> {
+        if self.is_blocked(course) {
+            if let Some(floating) = &mut self.floating {
+                // waits for the next click instead
+                floating.is_dragged = false;
+            }
+            return None;
+        }
+        let tiles = self
+            .floating
+            .take()
+            .map(|FloatingTiles { tiles, pos, .. }| {
+                tiles
+                    .into_iter()
+                    .map(|([x, y], tile_variant)| SpawnTileEvent {
+                        tile_variant,
+                        grid_pos: [pos[0] + x, pos[1] + y],
+                    })
+                    .collect()
+            });
+        Some(tiles.unwrap_or_default())
+    }
+
+    /// Places the floating tiles where they are.
+    ///
+    /// Returns whether no tiles are floating anymore.
+    fn drop_floating(
+        &mut self,
+        course: &CourseRes,
+        spawn_tile_events: &mut EventWriter<SpawnTileEvent>,
+    ) -> bool {
+        if let Some(tiles) = self.take_floating(course) {
+            spawn_tile_events.send_batch(tiles);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Ends the floating, e.g. when the selection is left, and returns the tiles to place.
+    ///
+    /// Blocked tiles return to where they were lifted from, blocked pastes are discarded.
+    fn put_down_floating(&mut self, course: &CourseRes) -> Vec<SpawnTileEvent> {
+        if let Some(tiles) = self.take_floating(course) {
+            return tiles;
+        }
+        if let Some(origin) = self.floating.as_ref().and_then(|floating| floating.origin) {
+            self.move_floating_to(origin);
+            self.take_floating(course).unwrap_or_default()
+        } else {
+            self.floating = None;
+            vec![]
+        }
+    }
+}
+
+fn despawn_tiles(
+    despawn_tile_events: &mut EventWriter<DespawnTileEvent>,
+    tiles: &HashMap<[i32; 2], TileVariant>,
+    pos: [i32; 2],
+) {
+    despawn_tile_events.send_batch(tiles.keys().map(|[x, y]| DespawnTileEvent {
+        grid_pos: [pos[0] + x, pos[1] + y],
+        ..Default::default()
+    }));
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn select(
+    mouse_button_input: Res<Input<MouseButton>>,
+    keyboard_input: Res<Input<KeyCode>>,
+    windows: Res<Windows>,
+    camera_query: MainCameraQuery,
+    interaction_query: Query<&Interaction>,
+    not_editable_query: Query<(), With<TileNotEditable>>,
+    course: Res<CourseRes>,
+    game_mode: Res<GameMode>,
+    tool: Res<EditorTool>,
+    dragging: Res<Dragging>,
+    mut selection: ResMut<Selection>,
+    mut spawn_tile_events: EventWriter<SpawnTileEvent>,
+    mut despawn_tile_events: EventWriter<DespawnTileEvent>,
+) {
+    let is_active =
+        *tool == EditorTool::Select && matches!(*game_mode, GameMode::Build { is_editing: true });
+    if !is_active {
+        if selection.corners.is_some() {
+            let tiles = selection.put_down_floating(&course);
+            spawn_tile_events.send_batch(tiles);
+            if selection.floating.is_none() {
+                selection.corners = None;
+            }
+            selection.is_selecting = false;
+        }
+        return;
+    }
+
+    let window = windows.get_primary().unwrap();
+    let cursor = window.cursor_position().map(|cursor_position| {
+        world_to_grid(&cursor_to_world(cursor_position, &camera_query, window))
+    });
+
+    if keyboard_input.any_pressed([KeyCode::LControl, KeyCode::RControl]) {
+        if keyboard_input.any_just_pressed([KeyCode::C, KeyCode::X]) {
+            let tiles = selection.editable_tiles(&course, &not_editable_query);
+            if !tiles.is_empty() {
+                if keyboard_input.just_pressed(KeyCode::X) {
+                    let (min, _) = selection.bounds().unwrap();
+                    despawn_tiles(&mut despawn_tile_events, &tiles, min);
+                }
+                selection.clipboard = tiles;
+            }
+        }
+        if keyboard_input.just_pressed(KeyCode::V) && !selection.clipboard.is_empty() {
+            if let Some(cursor) = cursor {
+                if selection.drop_floating(&course, &mut spawn_tile_events) {
+                    let tiles = selection.clipboard.clone();
+                    selection.float(tiles, cursor, [0, 0], None);
+                }
+            }
+        }
+    }
+    if keyboard_input.just_pressed(KeyCode::Delete) {
+        if let Some(floating) = &selection.floating {
+            if floating.origin.is_none() {
+                // discards the paste
+                selection.floating = None;
+                selection.corners = None;
+            }
+        } else if let Some((min, _)) = selection.bounds() {
+            let tiles = selection.editable_tiles(&course, &not_editable_query);
+            despawn_tiles(&mut despawn_tile_events, &tiles, min);
+        }
+    }
+    if keyboard_input.just_pressed(KeyCode::Escape) {
+        let tiles = selection.put_down_floating(&course);
+        spawn_tile_events.send_batch(tiles);
+        if selection.floating.is_none() {
+            selection.corners = None;
+        }
+    }
+
+    let cursor = if let Some(cursor) = cursor {
+        cursor
+    } else {
+        return;
+    };
+    if mouse_button_input.just_released(MouseButton::Left) {
+        selection.is_selecting = false;
+        if selection
+            .floating
+            .as_ref()
+            .is_some_and(|floating| floating.is_dragged)
+        {
+            selection.drop_floating(&course, &mut spawn_tile_events);
+        }
+    }
+    selection.move_floating(cursor);
+    if selection.is_selecting && mouse_button_input.pressed(MouseButton::Left) {
+        if let Some([_, corner]) = &mut selection.corners {
+            if *corner != cursor {
+                *corner = cursor;
+            }
+        }
+    }
+
+    let is_over_ui = interaction_query
+        .iter()
+        .any(|interaction| *interaction != Interaction::None);
+    if is_over_ui || dragging.0.is_some() || !mouse_button_input.just_pressed(MouseButton::Left) {
+        return;
+    }
+    if selection.floating.is_some() {
+        selection.drop_floating(&course, &mut spawn_tile_events);
+    } else if selection.contains(cursor) {
+        let tiles = selection.editable_tiles(&course, &not_editable_query);
+        let (min, _) = selection.bounds().unwrap();
+        despawn_tiles(&mut despawn_tile_events, &tiles, min);
+        selection.float(
+            tiles,
+            min,
+            [cursor[0] - min[0], cursor[1] - min[1]],
+            Some(min),
+        );
+    } else {
+        selection.corners = Some([cursor, cursor]);
+        selection.is_selecting = true;
+    }
+}
+
+/// Places the floating tiles right away before play mode or an area switch takes the course,
+/// which misses them while they float.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn put_down_before_leaving(
+    mut commands: Commands,
+    mut play_mode_events: EventReader<PlayModeEvent>,
+    mut switch_area_events: EventReader<SwitchAreaEvent>,
+    mut course: ResMut<CourseRes>,
+    mut selection: ResMut<Selection>,
+    mut history: ResMut<EditHistory>,
+    mut ground_tile_update_events: EventWriter<GroundTileUpdateEvent>,
+) {
+    let is_playing = play_mode_events
+        .iter()
+        .any(|event| matches!(event, PlayModeEvent::Start | PlayModeEvent::Replay(_)));
+    let is_switching = switch_area_events
+        .iter()
+        .any(|SwitchAreaEvent(area)| *area != course.area);
+    if !(is_playing || is_switching) || selection.floating.is_none() {
+        return;
+    }
+
+    let mut events = HashMap::new();
+    for SpawnTileEvent {
+        grid_pos,
+        tile_variant,
+    } in selection.put_down_floating(&course)
+    {
+        course.spawn_tile(&mut commands, &grid_pos, &tile_variant, &mut events);
+        history.record(
+            course.area,
+            EditCommand::PlaceTile {
+                grid_pos,
+                variant: tile_variant,
+            },
+        );
+    }
+    if selection.floating.is_none() {
+        selection.corners = None;
+    }
+    ground_tile_update_events.send_batch(events.into_values());
+}
+
+/// Tells the autosave whether tiles are missing from the course while they float.
+pub(crate) fn update_tiles_lifted(
+    selection: Res<Selection>,
+    mut tiles_lifted: ResMut<TilesLifted>,
+) {
+    let is_lifted = selection
+        .floating
+        .as_ref()
+        .is_some_and(|floating| floating.origin.is_some());
+    if tiles_lifted.0 != is_lifted {
+        tiles_lifted.0 = is_lifted;
+    }
+}
+
+pub(crate) fn update_selection_visuals(
+    mut commands: Commands,
+    query: Query<Entity, With<SelectionVisual>>,
+    selection: Res<Selection>,
+    course: Res<CourseRes>,
+    mut last_key: Local<SelectionVisualKey>,
+) {
+    // the selection is borrowed mutably every frame, so change detection does not help
+    let key = selection.visual_key(&course);
+    if key == *last_key {
+        return;
+    }
+    let is_blocked = key.is_blocked;
+    *last_key = key;
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    if let Some((min, max)) = selection.bounds() {
+        let center =
+            grid_to_world_f32(&[(min[0] + max[0]) as f32 / 2., (min[1] + max[1]) as f32 / 2.]);
+        let size = grid_to_world(&[max[0] - min[0] + 1, max[1] - min[1] + 1]);
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: if is_blocked {
+                        SELECTION_BLOCKED_COLOR
+                    } else {
+                        SELECTION_COLOR
+                    },
+                    custom_size: Some(size),
+                    ..Default::default()
+                },
+                transform: Transform::from_xyz(center.x, center.y, Z_INDEX_SELECTION),
+                ..Default::default()
+            },
+            SelectionVisual,
+        ));
+    }
+
+    if let Some(FloatingTiles { tiles, pos, .. }) = &selection.floating {
+        commands
+            .spawn((SpatialBundle::default(), SelectionVisual))
+            .with_children(|parent| {
+                for ([x, y], tile_variant) in tiles {
+                    let world_pos = grid_to_world(&[pos[0] + x, pos[1] + y]);
+                    parent.spawn(SpriteSheetBundle {
+                        transform: Transform {
+                            translation: Vec3::new(world_pos.x, world_pos.y, Z_INDEX_FLOATING_TILE),
+                            scale: Vec3::new(TILE_SIZE, TILE_SIZE, 0.),
+                            ..Default::default()
+                        },
+                        texture_atlas: course.texture_atlas_handle_transparent.clone(),
+                        sprite: TextureAtlasSprite::new(tile_variant.get_sprite_sheet_index()),
+                        ..Default::default()
+                    });
+                }
+            });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shrm_core::{AreaId, Course, CourseArea, CourseMetadata, ThemeVariant};
+
+    const LIFTED: [[i32; 2]; 2] = [[10, 5], [11, 5]];
+
+    /// Takes the course on play and restores it on stop, like `toggle_play_mode`.
+    fn play(
+        mut commands: Commands,
+        mut play_mode_events: EventReader<PlayModeEvent>,
+        mut course: ResMut<CourseRes>,
+        mut editor_course: Local<Option<Course>>,
+    ) {
+        for event in play_mode_events.iter() {
+            match event {
+                PlayModeEvent::Start => *editor_course = Some(Course::from(&*course)),
+                PlayModeEvent::Stop => {
+                    let editor_course = editor_course.take().unwrap();
+                    course.unload_area(&mut commands);
+                    for (grid_pos, tile_variant) in editor_course.main_area.tiles.iter() {
+                        course.spawn_tile(
+                            &mut commands,
+                            grid_pos,
+                            tile_variant,
+                            &mut HashMap::new(),
+                        );
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.init_resource::<Selection>()
+            .init_resource::<EditHistory>()
+            .add_event::<PlayModeEvent>()
+            .add_event::<SwitchAreaEvent>()
+            .add_event::<GroundTileUpdateEvent>()
+            .add_system_to_stage(CoreStage::PreUpdate, put_down_before_leaving)
+            .add_system(play)
+            .insert_resource(CourseRes {
+                texture_atlas_handle: Handle::default(),
+                texture_atlas_handle_transparent: Handle::default(),
+                tiles: HashMap::default(),
+                theme: ThemeVariant::Plain,
+                area: AreaId::Main,
+                width: 100,
+                height: 20,
+                unloaded_areas: HashMap::from([(
+                    AreaId::Sub,
+                    CourseArea::new(ThemeVariant::Underground),
+                )]),
+                links: vec![],
+                start_pos: [0, 0],
+                goal_pos_x: 80,
+                metadata: CourseMetadata::default(),
+            });
+        app
+    }
+
+    /// Lifts the tiles at `LIFTED` off the course, as a click into the selection does.
+    fn lift(app: &mut App) {
+        let mut course = app.world.resource_mut::<CourseRes>();
+        let tiles = LIFTED
+            .iter()
+            .map(|[x, y]| {
+                let tile = course.tiles.remove(&[*x, *y]).unwrap();
+                ([x - LIFTED[0][0], y - LIFTED[0][1]], tile.variant)
+            })
+            .collect();
+        let mut selection = app.world.resource_mut::<Selection>();
+        selection.float(tiles, LIFTED[0], [0, 0], Some(LIFTED[0]));
+    }
+
+    fn tile_positions(app: &App) -> Vec<[i32; 2]> {
+        let mut positions: Vec<_> = app
+            .world
+            .resource::<CourseRes>()
+            .tiles
+            .keys()
+            .copied()
+            .collect();
+        positions.sort();
+        positions
+    }
+
+    #[test]
+    fn lifted_tiles_survive_play_mode() {
+        let mut app = app();
+        app.world
+            .resource_scope(|world, mut course: Mut<CourseRes>| {
+                let mut queue = bevy::ecs::system::CommandQueue::default();
+                let mut commands = Commands::new(&mut queue, world);
+                for grid_pos in LIFTED {
+                    course.spawn_tile(
+                        &mut commands,
+                        &grid_pos,
+                        &TileVariant::HardBlock,
+                        &mut HashMap::new(),
+                    );
+                }
+                queue.apply(world);
+            });
+        lift(&mut app);
+        app.world
+            .resource_mut::<Selection>()
+            .move_floating_to([20, 8]);
+
+        app.world.send_event(PlayModeEvent::Start);
+        app.update();
+        assert!(app.world.resource::<Selection>().floating.is_none());
+        app.world.send_event(PlayModeEvent::Stop);
+        app.update();
+
+        assert_eq!(tile_positions(&app), vec![[20, 8], [21, 8]]);
+    }
+}
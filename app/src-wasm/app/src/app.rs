@@ -46,8 +46,8 @@ pub fn main(assets: Vec<u8>) {
     .add_plugin(CoursePlugin)
     .add_plugin(MenuPlugin)
     .add_plugin(LoadPlugin)
-    .add_system_set(SystemSet::on_enter(AppState::Menu).with_system(setup_camera))
     .add_system_set(SystemSet::on_enter(AppState::Game).with_system(setup_graphics))
+    .add_startup_system(setup_camera)
     .add_startup_system(setup_resolution_scaling)
     .add_startup_system(keyboard_input_system)
     .add_system_set_to_stage(CoreStage::First, State::<AppState>::get_driver())
@@ -1,9 +1,17 @@
 pub mod app;
 pub mod graphics;
 
+use std::{panic, sync::Once};
 use wasm_bindgen::prelude::*;
 
 #[wasm_bindgen(js_name = setupPanicHook)]
 pub fn setup_panic_hook() {
-    console_error_panic_hook::set_once();
+    static SET_HOOK: Once = Once::new();
+    SET_HOOK.call_once(|| {
+        panic::set_hook(Box::new(|info| {
+            console_error_panic_hook::hook(info);
+            // release builds abort on panic, so this is the last chance to keep the course
+            app_core::save_autosave();
+        }));
+    });
 }
@@ -0,0 +1,334 @@
+//! Ground autotiling.
+//!
+//! Every ground tile picks its [`GroundVariant`] from which of its eight neighbours are ground.
+//! Positions left of or below the course count as ground, so that the course edges stay seamless.
+
+use crate::{CourseArea, GroundVariant, TileVariant};
+use std::collections::{HashMap, HashSet};
+
+/// A value of a tile map that can be autotiled.
+pub trait MapTile {
+    fn variant(&self) -> &TileVariant;
+    fn variant_mut(&mut self) -> &mut TileVariant;
+}
+
+impl MapTile for TileVariant {
+    fn variant(&self) -> &TileVariant {
+        self
+    }
+
+    fn variant_mut(&mut self) -> &mut TileVariant {
+        self
+    }
+}
+
+/// Returns which neighbours of `pos` are ground.
+///
+/// The matrix is indexed as `matrix[row][col]` with the top row first,
+/// so the neighbour at offset `(dx, dy)` is stored at `matrix[1 - dy][dx + 1]`.
+pub fn surrounding_matrix<T: MapTile>(
+    tiles: &HashMap<[i32; 2], T>,
+    pos: &[i32; 2],
+) -> [[bool; 3]; 3] {
+    let mut matrix = [[false; 3]; 3];
+    for dy in -1..=1 {
+        for dx in -1..=1 {
+            if dx == 0 && dy == 0 {
+                continue;
+            }
+            matrix[(1 - dy) as usize][(dx + 1) as usize] =
+                is_ground(tiles, &[pos[0] + dx, pos[1] + dy]);
+        }
+    }
+    matrix
+}
+
+/// Recomputes the variant of every ground tile and returns the positions that changed.
+pub fn retile<T: MapTile>(tiles: &mut HashMap<[i32; 2], T>) -> Vec<[i32; 2]> {
+    let positions: Vec<_> = tiles.keys().copied().collect();
+    positions
+        .into_iter()
+        .filter(|pos| retile_at(tiles, pos))
+        .collect()
+}
+
+/// Recomputes the variants of the ground tiles at and around `pos`
+/// and returns the positions that changed.
+///
+/// Call this after placing or removing a tile at `pos`.
+pub fn retile_around<T: MapTile>(
+    tiles: &mut HashMap<[i32; 2], T>,
+    pos: &[i32; 2],
+) -> Vec<[i32; 2]> {
+    let mut changed = vec![];
+    for dy in -1..=1 {
+        for dx in -1..=1 {
+            let pos = [pos[0] + dx, pos[1] + dy];
+            if retile_at(tiles, &pos) {
+                changed.push(pos);
+            }
+        }
+    }
+    changed
+}
+
+/// Returns the variants the ground would get if `variant` was placed at every empty cell of `cells`.
+///
+/// Contains the placed tiles and every existing tile whose variant would change,
+/// without changing `tiles` itself.
+pub fn preview_placement<T: MapTile>(
+    tiles: &HashMap<[i32; 2], T>,
+    cells: &[[i32; 2]],
+    variant: &TileVariant,
+) -> HashMap<[i32; 2], TileVariant> {
+    // retiling around a cell looks up to two cells away
+    let mut preview = HashMap::new();
+    for [x, y] in cells {
+        for dy in -2..=2 {
+            for dx in -2..=2 {
+                let pos = [x + dx, y + dy];
+                if let Some(tile) = tiles.get(&pos) {
+                    preview.insert(pos, tile.variant().clone());
+                }
+            }
+        }
+    }
+    let placed: HashSet<_> = cells
+        .iter()
+        .filter(|cell| !tiles.contains_key(*cell))
+        .copied()
+        .collect();
+    for cell in placed.iter() {
+        preview.insert(*cell, variant.clone());
+    }
+    for cell in placed.iter() {
+        retile_around(&mut preview, cell);
+    }
+    preview
+        .into_iter()
+        .filter(|(pos, variant)| {
+            placed.contains(pos) || tiles.get(pos).map(MapTile::variant) != Some(variant)
+        })
+        .collect()
+}
+
+fn retile_at<T: MapTile>(tiles: &mut HashMap<[i32; 2], T>, pos: &[i32; 2]) -> bool {
+    if !matches!(
+        tiles.get(pos).map(MapTile::variant),
+        Some(TileVariant::Ground(_))
+    ) {
+        return false;
+    }
+    let new_variant = GroundVariant::from_surrounding_matrix(&surrounding_matrix(tiles, pos));
+    if let Some(TileVariant::Ground(ground_variant)) = tiles.get_mut(pos).map(MapTile::variant_mut)
+    {
+        if *ground_variant != new_variant {
+            *ground_variant = new_variant;
+            return true;
+        }
+    }
+    false
+}
+
+fn is_ground<T: MapTile>(tiles: &HashMap<[i32; 2], T>, pos: &[i32; 2]) -> bool {
+    pos[0] < 0
+        || pos[1] < 0
+        || matches!(
+            tiles.get(pos).map(MapTile::variant),
+            Some(TileVariant::Ground(_))
+        )
+}
+
+impl CourseArea {
+    /// See [`retile`].
+    pub fn retile(&mut self) -> Vec<[i32; 2]> {
+        retile(&mut self.tiles)
+    }
+
+    /// See [`retile_around`].
+    pub fn retile_around(&mut self, pos: &[i32; 2]) -> Vec<[i32; 2]> {
+        retile_around(&mut self.tiles, pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CENTER: [i32; 2] = [10, 10];
+
+    /// Neighbour offsets in the order of the bits of a neighbour mask.
+    const OFFSETS: [[i32; 2]; 8] = [
+        [-1, 1],
+        [0, 1],
+        [1, 1],
+        [-1, 0],
+        [1, 0],
+        [-1, -1],
+        [0, -1],
+        [1, -1],
+    ];
+
+    fn ground() -> TileVariant {
+        TileVariant::Ground(GroundVariant::default())
+    }
+
+    fn neighbours(mask: u8) -> impl Iterator<Item = [i32; 2]> {
+        OFFSETS
+            .into_iter()
+            .enumerate()
+            .filter(move |(bit, _)| mask & (1 << bit) != 0)
+            .map(|(_, [dx, dy])| [CENTER[0] + dx, CENTER[1] + dy])
+    }
+
+    fn expected_matrix(mask: u8) -> [[bool; 3]; 3] {
+        let mut matrix = [[false; 3]; 3];
+        for (bit, [dx, dy]) in OFFSETS.into_iter().enumerate() {
+            matrix[(1 - dy) as usize][(dx + 1) as usize] = mask & (1 << bit) != 0;
+        }
+        matrix
+    }
+
+    fn area(tiles: HashMap<[i32; 2], TileVariant>) -> CourseArea {
+        CourseArea {
+            tiles,
+            ..CourseArea::new(crate::ThemeVariant::Plain)
+        }
+    }
+
+    fn area_with(mask: u8) -> CourseArea {
+        let mut tiles: HashMap<_, _> = neighbours(mask).map(|pos| (pos, ground())).collect();
+        tiles.insert(CENTER, ground());
+        area(tiles)
+    }
+
+    fn retiled(mut area: CourseArea) -> HashMap<[i32; 2], TileVariant> {
+        area.retile();
+        area.tiles
+    }
+
+    #[test]
+    fn matrix_matches_neighbours() {
+        for mask in 0..=u8::MAX {
+            let area = area_with(mask);
+            assert_eq!(
+                surrounding_matrix(&area.tiles, &CENTER),
+                expected_matrix(mask),
+                "mask {mask:08b}"
+            );
+        }
+    }
+
+    #[test]
+    fn retile_uses_matrix() {
+        for mask in 0..=u8::MAX {
+            let tiles = retiled(area_with(mask));
+            assert_eq!(
+                tiles[&CENTER],
+                TileVariant::Ground(GroundVariant::from_surrounding_matrix(&expected_matrix(
+                    mask
+                ))),
+                "mask {mask:08b}"
+            );
+        }
+    }
+
+    #[test]
+    fn retile_is_idempotent() {
+        for mask in 0..=u8::MAX {
+            let mut area = area_with(mask);
+            area.retile();
+            assert!(area.retile().is_empty(), "mask {mask:08b}");
+        }
+    }
+
+    #[test]
+    fn incremental_placement_matches_retile() {
+        for mask in 0..=u8::MAX {
+            let mut area = area(HashMap::new());
+            for pos in neighbours(mask).chain(std::iter::once(CENTER)) {
+                area.tiles.insert(pos, ground());
+                area.retile_around(&pos);
+            }
+            assert_eq!(area.tiles, retiled(area_with(mask)), "mask {mask:08b}");
+        }
+    }
+
+    #[test]
+    fn incremental_removal_matches_retile() {
+        for mask in 0..=u8::MAX {
+            let mut area = area_with(u8::MAX);
+            area.retile();
+            for pos in neighbours(!mask) {
+                area.tiles.remove(&pos);
+                area.retile_around(&pos);
+            }
+            assert_eq!(area.tiles, retiled(area_with(mask)), "mask {mask:08b}");
+        }
+    }
+
+    #[test]
+    fn retile_around_reports_changes() {
+        for mask in 0..=u8::MAX {
+            let mut area = area_with(mask);
+            area.retile();
+            let before = area.tiles.clone();
+            area.tiles.remove(&CENTER);
+            let changed = area.retile_around(&CENTER);
+            for pos in neighbours(mask) {
+                assert_eq!(
+                    changed.contains(&pos),
+                    before[&pos] != area.tiles[&pos],
+                    "mask {mask:08b}, pos {pos:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn preview_matches_placement() {
+        for mask in 0..=u8::MAX {
+            let mut area = area_with(mask);
+            area.tiles.remove(&CENTER);
+            area.retile();
+            let before = area.tiles.clone();
+            let preview = preview_placement(&area.tiles, &[CENTER], &ground());
+            assert_eq!(area.tiles, before, "mask {mask:08b}");
+
+            area.tiles.insert(CENTER, ground());
+            area.retile_around(&CENTER);
+            let changed: HashMap<_, _> = area
+                .tiles
+                .iter()
+                .filter(|(pos, variant)| before.get(*pos) != Some(*variant))
+                .map(|(pos, variant)| (*pos, variant.clone()))
+                .collect();
+            assert_eq!(preview, changed, "mask {mask:08b}");
+        }
+    }
+
+    #[test]
+    fn outside_of_course_counts_as_ground() {
+        let mut tiles = HashMap::from([([0, 0], ground())]);
+        retile(&mut tiles);
+        assert_eq!(
+            surrounding_matrix(&tiles, &[0, 0]),
+            [
+                [true, false, false],
+                [true, false, false],
+                [true, true, true]
+            ]
+        );
+    }
+
+    #[test]
+    fn other_tiles_are_left_alone() {
+        let mut tiles = HashMap::from([([10, 10], TileVariant::HardBlock), ([11, 10], ground())]);
+        retile(&mut tiles);
+        assert_eq!(tiles[&[10, 10]], TileVariant::HardBlock);
+        assert_eq!(
+            tiles[&[11, 10]],
+            TileVariant::Ground(GroundVariant::from_surrounding_matrix(&[[false; 3]; 3]))
+        );
+    }
+}
@@ -1,38 +1,271 @@
-use crate::{ThemeVariant, TileVariant};
-use anyhow::Result;
+mod area;
+mod metadata;
+mod migration;
+mod tile_runs;
+mod validation;
+
+pub use area::{AreaId, AreaLink, AreaPos, CourseArea};
+pub use metadata::CourseMetadata;
+pub use validation::CourseValidationError;
+
+use crate::PLAYER_START_OFFSET;
+use anyhow::{bail, Result};
 use brotli::{
     enc::{backward_references::BrotliEncoderMode, BrotliEncoderParams},
     BrotliCompress, BrotliDecompress,
 };
-use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, io::Cursor};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::io::Cursor;
+
+/// Magic bytes every framed course file starts with.
+pub const COURSE_MAGIC: [u8; 4] = *b"SHRM";
+
+/// Format version written by [`Course::serialize`].
+///
+/// Files without the [`COURSE_MAGIC`] header are treated as version 0.
+/// Bump this whenever `Course` or any type it contains changes its serialized shape
+/// and add a migration step for the previous version.
+pub const COURSE_FORMAT_VERSION: u16 = 5;
+
+/// First format version whose header carries a [`CourseEncoding`] byte.
+const ENCODING_BYTE_VERSION: u16 = 2;
+
+/// How the payload following the header is encoded.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum CourseEncoding {
+    /// Brotli compressed RON, the only encoding before format version 2.
+    Ron,
+    /// Postcard with the tile map stored as row-wise runs of equal tiles.
+    #[default]
+    Binary,
+}
+
+impl CourseEncoding {
+    fn from_byte(byte: u8) -> Result<Self> {
+        Ok(match byte {
+            0 => Self::Ron,
+            1 => Self::Binary,
+            _ => bail!("unknown course encoding {byte}"),
+        })
+    }
+
+    fn as_byte(&self) -> u8 {
+        match self {
+            Self::Ron => 0,
+            Self::Binary => 1,
+        }
+    }
+}
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Course {
-    pub tiles: HashMap<[i32; 2], TileVariant>,
-    pub theme: ThemeVariant,
+    pub main_area: CourseArea,
+    pub sub_area: CourseArea,
+    pub links: Vec<AreaLink>,
+    /// Bottom left corner of the start area in the main area.
+    pub start_pos: [i32; 2],
     pub goal_pos_x: i32,
+    pub metadata: CourseMetadata,
 }
 
 impl Course {
+    pub fn area(&self, area: AreaId) -> &CourseArea {
+        match area {
+            AreaId::Main => &self.main_area,
+            AreaId::Sub => &self.sub_area,
+        }
+    }
+
+    pub fn area_mut(&mut self, area: AreaId) -> &mut CourseArea {
+        match area {
+            AreaId::Main => &mut self.main_area,
+            AreaId::Sub => &mut self.sub_area,
+        }
+    }
+
+    /// Grid position the player spawns at.
+    pub fn player_start(&self) -> [i32; 2] {
+        [
+            self.start_pos[0] + PLAYER_START_OFFSET[0],
+            self.start_pos[1] + PLAYER_START_OFFSET[1],
+        ]
+    }
+
     pub fn serialize(&self) -> Result<Vec<u8>> {
-        let course_as_str = ron::to_string(self)?;
-        let mut input = Cursor::new(course_as_str.as_bytes());
-        let mut compressed = vec![];
-        let params = BrotliEncoderParams {
-            mode: BrotliEncoderMode::BROTLI_MODE_TEXT,
-            quality: 11,
-            ..Default::default()
-        };
-        BrotliCompress(&mut input, &mut compressed, &params)?;
-        Ok(compressed)
+        self.serialize_with(CourseEncoding::default())
+    }
+
+    pub fn serialize_with(&self, encoding: CourseEncoding) -> Result<Vec<u8>> {
+        let mut buf = vec![];
+        buf.extend_from_slice(&COURSE_MAGIC);
+        buf.extend_from_slice(&COURSE_FORMAT_VERSION.to_le_bytes());
+        buf.push(encoding.as_byte());
+        encode(self, encoding, &mut buf)?;
+        Ok(buf)
     }
 
     pub fn deserialize(buf: Vec<u8>) -> Result<Self> {
-        let mut input = Cursor::new(buf);
-        let mut decompressed = vec![];
-        BrotliDecompress(&mut input, &mut decompressed)?;
-        let course_as_str = String::from_utf8(decompressed)?;
-        Ok(ron::from_str(&course_as_str)?)
+        let (version, encoding, payload) = Self::read_header(&buf)?;
+        migration::migrate(version, encoding, payload)
+    }
+
+    fn read_header(buf: &[u8]) -> Result<(u16, CourseEncoding, &[u8])> {
+        let payload = if let Some(payload) = buf.strip_prefix(&COURSE_MAGIC) {
+            payload
+        } else {
+            return Ok((0, CourseEncoding::Ron, buf));
+        };
+        if payload.len() < std::mem::size_of::<u16>() {
+            bail!("course header is truncated");
+        }
+        let (version, payload) = payload.split_at(std::mem::size_of::<u16>());
+        let version = u16::from_le_bytes([version[0], version[1]]);
+        if version > COURSE_FORMAT_VERSION {
+            bail!(
+                "course format version {version} is newer than the supported version {COURSE_FORMAT_VERSION}"
+            );
+        }
+        if version < ENCODING_BYTE_VERSION {
+            return Ok((version, CourseEncoding::Ron, payload));
+        }
+        if let Some((encoding, payload)) = payload.split_first() {
+            Ok((version, CourseEncoding::from_byte(*encoding)?, payload))
+        } else {
+            bail!("course header is truncated");
+        }
+    }
+}
+
+fn encode<T: Serialize>(value: &T, encoding: CourseEncoding, buf: &mut Vec<u8>) -> Result<()> {
+    match encoding {
+        CourseEncoding::Ron => {
+            let course_as_str = ron::to_string(value)?;
+            let mut input = Cursor::new(course_as_str.as_bytes());
+            let params = BrotliEncoderParams {
+                mode: BrotliEncoderMode::BROTLI_MODE_TEXT,
+                quality: 11,
+                ..Default::default()
+            };
+            BrotliCompress(&mut input, buf, &params)?;
+        }
+        CourseEncoding::Binary => {
+            buf.extend(postcard::to_allocvec(value)?);
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn decode<T: DeserializeOwned>(encoding: CourseEncoding, payload: &[u8]) -> Result<T> {
+    match encoding {
+        CourseEncoding::Ron => {
+            let mut input = Cursor::new(payload);
+            let mut decompressed = vec![];
+            BrotliDecompress(&mut input, &mut decompressed)?;
+            let course_as_str = String::from_utf8(decompressed)?;
+            Ok(ron::from_str(&course_as_str)?)
+        }
+        CourseEncoding::Binary => Ok(postcard::from_bytes(payload)?),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GroundVariant, ThemeVariant, TileVariant};
+
+    fn course() -> Course {
+        let mut main_area = CourseArea::new(ThemeVariant::Plain);
+        for x in 0..main_area.width {
+            main_area
+                .tiles
+                .insert([x, 0], TileVariant::Ground(GroundVariant::default()));
+        }
+        main_area.tiles.insert([12, 4], TileVariant::HardBlock);
+        Course {
+            main_area,
+            sub_area: CourseArea::new(ThemeVariant::Underground),
+            links: vec![],
+            start_pos: [0, 0],
+            goal_pos_x: 50,
+            metadata: CourseMetadata {
+                title: "Roundtrip".to_string(),
+                tags: vec!["test".to_string()],
+                ..Default::default()
+            },
+        }
+    }
+
+    fn assert_roundtrip(encoding: CourseEncoding) {
+        let course = course();
+        let loaded = Course::deserialize(course.serialize_with(encoding).unwrap()).unwrap();
+        assert_eq!(loaded.main_area.tiles, course.main_area.tiles);
+        assert_eq!(loaded.main_area.width, course.main_area.width);
+        assert_eq!(loaded.sub_area.theme, course.sub_area.theme);
+        assert_eq!(loaded.goal_pos_x, course.goal_pos_x);
+        assert_eq!(loaded.metadata, course.metadata);
+    }
+
+    #[test]
+    fn ron_roundtrip() {
+        assert_roundtrip(CourseEncoding::Ron);
+    }
+
+    #[test]
+    fn binary_roundtrip() {
+        assert_roundtrip(CourseEncoding::Binary);
+    }
+
+    fn header(version: u16) -> Vec<u8> {
+        let mut buf = COURSE_MAGIC.to_vec();
+        buf.extend_from_slice(&version.to_le_bytes());
+        buf
+    }
+
+    #[test]
+    fn header_roundtrip() {
+        let buf = course().serialize().unwrap();
+        let (version, encoding, _) = Course::read_header(&buf).unwrap();
+        assert_eq!(version, COURSE_FORMAT_VERSION);
+        assert_eq!(encoding, CourseEncoding::default());
+    }
+
+    #[test]
+    fn rejects_unknown_version() {
+        let mut buf = course().serialize().unwrap();
+        buf[COURSE_MAGIC.len()..COURSE_MAGIC.len() + 2]
+            .copy_from_slice(&(COURSE_FORMAT_VERSION + 1).to_le_bytes());
+        assert!(Course::deserialize(buf).is_err());
+    }
+
+    #[test]
+    fn rejects_unknown_encoding() {
+        let mut buf = header(COURSE_FORMAT_VERSION);
+        buf.push(u8::MAX);
+        assert!(Course::deserialize(buf).is_err());
+    }
+
+    #[test]
+    fn rejects_truncated_header() {
+        let mut truncated_version = COURSE_MAGIC.to_vec();
+        truncated_version.push(COURSE_FORMAT_VERSION as u8);
+        assert!(Course::deserialize(truncated_version).is_err());
+        assert!(Course::deserialize(header(COURSE_FORMAT_VERSION)).is_err());
+        assert!(Course::deserialize(COURSE_MAGIC.to_vec()).is_err());
+        assert!(Course::deserialize(vec![]).is_err());
+    }
+
+    #[test]
+    fn rejects_bad_magic() {
+        let mut buf = course().serialize().unwrap();
+        buf[0] = b'X';
+        assert!(Course::deserialize(buf).is_err());
+    }
+
+    #[test]
+    fn rejects_truncated_payload() {
+        let buf = course().serialize().unwrap();
+        for len in COURSE_MAGIC.len()..buf.len() {
+            assert!(Course::deserialize(buf[..len].to_vec()).is_err());
+        }
     }
 }
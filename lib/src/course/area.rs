@@ -0,0 +1,68 @@
+use super::tile_runs;
+use crate::{
+    ThemeVariant, TileVariant, DEFAULT_COURSE_X, DEFAULT_COURSE_Y, MAX_COURSE_CELLS, MAX_COURSE_X,
+    MAX_COURSE_Y, MIN_COURSE_X, MIN_COURSE_Y,
+};
+use enum_iterator::Sequence;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Sequence, Serialize)]
+pub enum AreaId {
+    /// Holds the start and the goal.
+    Main,
+    Sub,
+}
+
+impl AreaId {
+    pub fn get_name(&self) -> &str {
+        match self {
+            AreaId::Main => "main",
+            AreaId::Sub => "sub",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct CourseArea {
+    #[serde(with = "tile_runs")]
+    pub tiles: HashMap<[i32; 2], TileVariant>,
+    pub theme: ThemeVariant,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl CourseArea {
+    pub fn new(theme: ThemeVariant) -> Self {
+        Self {
+            tiles: HashMap::new(),
+            theme,
+            width: DEFAULT_COURSE_X,
+            height: DEFAULT_COURSE_Y,
+        }
+    }
+
+    /// Whether `width` x `height` is within the limits of a course area.
+    pub fn is_valid_size(width: i32, height: i32) -> bool {
+        (MIN_COURSE_X..=MAX_COURSE_X).contains(&width)
+            && (MIN_COURSE_Y..=MAX_COURSE_Y).contains(&height)
+            && width * height <= MAX_COURSE_CELLS
+    }
+
+    pub fn contains(&self, pos: &[i32; 2]) -> bool {
+        (0..self.width).contains(&pos[0]) && (0..self.height).contains(&pos[1])
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct AreaPos {
+    pub area: AreaId,
+    pub pos: [i32; 2],
+}
+
+/// Connects two positions of a course, e.g. both ends of a pipe or a door.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct AreaLink {
+    pub from: AreaPos,
+    pub to: AreaPos,
+}
@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct CourseMetadata {
+    pub title: String,
+    pub description: String,
+    /// Account id of the author, if the course has been uploaded.
+    pub author_id: Option<String>,
+    /// Milliseconds since the Unix epoch.
+    pub created_at: u64,
+    /// Milliseconds since the Unix epoch.
+    pub modified_at: u64,
+    pub tags: Vec<String>,
+}
+
+impl CourseMetadata {
+    /// File name to export the course as, derived from its title.
+    pub fn file_name(&self) -> String {
+        let name: String = self
+            .title
+            .chars()
+            .map(|c| {
+                if c.is_alphanumeric() || c == '-' || c == '_' {
+                    c
+                } else {
+                    ' '
+                }
+            })
+            .collect();
+        let name = name.split_whitespace().collect::<Vec<_>>().join("_");
+        if name.is_empty() {
+            "course.shrm".to_string()
+        } else {
+            format!("{name}.shrm")
+        }
+    }
+}
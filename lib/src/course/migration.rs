@@ -0,0 +1,273 @@
+//! Upgrades courses written by older format versions.
+//!
+//! Whenever the schema changes, the previous `Course` layout gets frozen here as
+//! `CourseV{n}` together with a `From<CourseV{n}>` impl for the next version,
+//! so that every old file can be walked up to the current layout step by step.
+//! Nested structs are frozen as well, only enums are shared with the current layout,
+//! since new variants do not change how the old ones are read.
+
+use super::{decode, tile_runs, CourseEncoding};
+use crate::{
+    AreaId, AreaLink, AreaPos, Course, CourseArea, CourseMetadata, ThemeVariant, TileVariant,
+};
+use anyhow::{bail, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// Layout of format versions 0 to 2, which only differ by their framing.
+#[derive(Deserialize)]
+struct CourseV2 {
+    #[serde(deserialize_with = "tile_runs::deserialize")]
+    tiles: HashMap<[i32; 2], TileVariant>,
+    theme: ThemeVariant,
+    goal_pos_x: i32,
+}
+
+impl From<CourseV2> for CourseV3 {
+    fn from(course: CourseV2) -> Self {
+        Self {
+            tiles: course.tiles,
+            theme: course.theme,
+            goal_pos_x: course.goal_pos_x,
+            metadata: MetadataV4::default(),
+        }
+    }
+}
+
+/// Metadata of format versions 3 and 4.
+#[derive(Default, Deserialize)]
+struct MetadataV4 {
+    title: String,
+    description: String,
+    author_id: Option<String>,
+    created_at: u64,
+    modified_at: u64,
+    tags: Vec<String>,
+}
+
+impl From<MetadataV4> for CourseMetadata {
+    fn from(metadata: MetadataV4) -> Self {
+        Self {
+            title: metadata.title,
+            description: metadata.description,
+            author_id: metadata.author_id,
+            created_at: metadata.created_at,
+            modified_at: metadata.modified_at,
+            tags: metadata.tags,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct CourseV3 {
+    #[serde(deserialize_with = "tile_runs::deserialize")]
+    tiles: HashMap<[i32; 2], TileVariant>,
+    theme: ThemeVariant,
+    goal_pos_x: i32,
+    metadata: MetadataV4,
+}
+
+impl From<CourseV3> for CourseV4 {
+    fn from(course: CourseV3) -> Self {
+        Self {
+            main_area: AreaV4 {
+                tiles: course.tiles,
+                theme: course.theme,
+                // the fixed course size up to format version 3
+                width: 150,
+                height: 24,
+            },
+            // there was no sub area before format version 4
+            sub_area: AreaV4 {
+                tiles: HashMap::new(),
+                theme: ThemeVariant::Underground,
+                width: 150,
+                height: 24,
+            },
+            links: vec![],
+            goal_pos_x: course.goal_pos_x,
+            metadata: course.metadata,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct AreaV4 {
+    #[serde(deserialize_with = "tile_runs::deserialize")]
+    tiles: HashMap<[i32; 2], TileVariant>,
+    theme: ThemeVariant,
+    width: i32,
+    height: i32,
+}
+
+impl From<AreaV4> for CourseArea {
+    fn from(area: AreaV4) -> Self {
+        Self {
+            tiles: area.tiles,
+            theme: area.theme,
+            width: area.width,
+            height: area.height,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct AreaPosV4 {
+    area: AreaId,
+    pos: [i32; 2],
+}
+
+impl From<AreaPosV4> for AreaPos {
+    fn from(pos: AreaPosV4) -> Self {
+        Self {
+            area: pos.area,
+            pos: pos.pos,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct AreaLinkV4 {
+    from: AreaPosV4,
+    to: AreaPosV4,
+}
+
+impl From<AreaLinkV4> for AreaLink {
+    fn from(link: AreaLinkV4) -> Self {
+        Self {
+            from: link.from.into(),
+            to: link.to.into(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct CourseV4 {
+    main_area: AreaV4,
+    sub_area: AreaV4,
+    links: Vec<AreaLinkV4>,
+    goal_pos_x: i32,
+    metadata: MetadataV4,
+}
+
+impl From<CourseV4> for Course {
+    fn from(course: CourseV4) -> Self {
+        Self {
+            main_area: course.main_area.into(),
+            sub_area: course.sub_area.into(),
+            links: course.links.into_iter().map(AreaLink::from).collect(),
+            // the fixed start area up to format version 4
+            start_pos: [0, 0],
+            goal_pos_x: course.goal_pos_x,
+            metadata: course.metadata.into(),
+        }
+    }
+}
+
+pub(crate) fn migrate(version: u16, encoding: CourseEncoding, payload: &[u8]) -> Result<Course> {
+    match version {
+        0..=2 => Ok(CourseV4::from(CourseV3::from(decode::<CourseV2>(encoding, payload)?)).into()),
+        3 => Ok(CourseV4::from(decode::<CourseV3>(encoding, payload)?).into()),
+        4 => Ok(decode::<CourseV4>(encoding, payload)?.into()),
+        5 => decode(encoding, payload),
+        _ => bail!("unknown course format version {version}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AreaId, COURSE_FORMAT_VERSION, COURSE_MAGIC};
+    use brotli::{enc::BrotliEncoderParams, BrotliCompress};
+    use std::io::Cursor;
+
+    /// Frames a RON payload the way the given format version was written.
+    fn framed(version: u16, ron: &str) -> Vec<u8> {
+        let mut buf = COURSE_MAGIC.to_vec();
+        buf.extend_from_slice(&version.to_le_bytes());
+        if version >= 2 {
+            buf.push(CourseEncoding::Ron.as_byte());
+        }
+        BrotliCompress(
+            &mut Cursor::new(ron.as_bytes()),
+            &mut buf,
+            &BrotliEncoderParams::default(),
+        )
+        .unwrap();
+        buf
+    }
+
+    const METADATA: &str = r#"(title: "Old", description: "", author_id: None, created_at: 1, modified_at: 2, tags: ["retro"])"#;
+
+    #[test]
+    fn migrates_unframed_file() {
+        let course =
+            Course::deserialize(include_bytes!("../../../thunder-tests/course.ron.br").to_vec())
+                .unwrap();
+        assert_eq!(course.main_area.width, 150);
+        assert_eq!(course.main_area.height, 24);
+        assert!(!course.main_area.tiles.is_empty());
+        assert!(course.sub_area.tiles.is_empty());
+        assert!(course.links.is_empty());
+        assert_eq!(course.start_pos, [0, 0]);
+        assert_eq!(course.validate(), Ok(()));
+    }
+
+    #[test]
+    fn migrates_version_1() {
+        let course = Course::deserialize(framed(
+            1,
+            "(tiles: {(3, 4): HardBlock}, theme: Plain, goal_pos_x: 40)",
+        ))
+        .unwrap();
+        assert_eq!(
+            course.main_area.tiles.get(&[3, 4]),
+            Some(&TileVariant::HardBlock)
+        );
+        assert_eq!(course.goal_pos_x, 40);
+        assert_eq!(course.metadata, CourseMetadata::default());
+    }
+
+    #[test]
+    fn migrates_version_3() {
+        let course = Course::deserialize(framed(
+            3,
+            &format!(
+                "(tiles: {{(3, 4): HardBlock}}, theme: Plain, goal_pos_x: 40, metadata: {METADATA})"
+            ),
+        ))
+        .unwrap();
+        assert_eq!(
+            course.main_area.tiles.get(&[3, 4]),
+            Some(&TileVariant::HardBlock)
+        );
+        assert_eq!(course.main_area.width, 150);
+        assert_eq!(course.sub_area.theme, ThemeVariant::Underground);
+        assert_eq!(course.metadata.title, "Old");
+        assert_eq!(course.metadata.tags, ["retro"]);
+    }
+
+    #[test]
+    fn migrates_version_4() {
+        let area = "(tiles: {(3, 4): HardBlock}, theme: Plain, width: 60, height: 20)";
+        let link = "(from: (area: Main, pos: (1, 2)), to: (area: Sub, pos: (3, 4)))";
+        let course = Course::deserialize(framed(
+            4,
+            &format!(
+                "(main_area: {area}, sub_area: {area}, links: [{link}], goal_pos_x: 40, \
+                 metadata: {METADATA})"
+            ),
+        ))
+        .unwrap();
+        assert_eq!(course.main_area.width, 60);
+        assert_eq!(course.sub_area.height, 20);
+        assert_eq!(course.links[0].to.area, AreaId::Sub);
+        assert_eq!(course.start_pos, [0, 0]);
+        assert_eq!(course.goal_pos_x, 40);
+    }
+
+    #[test]
+    fn rejects_unknown_version() {
+        assert!(migrate(COURSE_FORMAT_VERSION + 1, CourseEncoding::Ron, &[]).is_err());
+    }
+}
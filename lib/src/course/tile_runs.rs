@@ -0,0 +1,169 @@
+//! Serde adapter for the tile map of a course.
+//!
+//! Human readable formats keep the plain `{[x, y]: variant}` map, so RON files stay unchanged.
+//! Binary formats instead store the tiles sorted row by row, with horizontally adjacent
+//! tiles of the same variant merged into a single run.
+//! Positions are stored relative to the end of the previous run,
+//! which keeps the varints of most runs at a single byte.
+
+use crate::{TileVariant, MAX_COURSE_X, MAX_COURSE_Y};
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::HashMap;
+
+/// Guards against hostile files expanding into huge tile maps.
+const MAX_TILES: usize = 1 << 20;
+
+#[derive(Deserialize, Serialize)]
+struct TileRun {
+    /// Offset to the end of the previous run, or to column 0 if the run starts a new row.
+    dx: i32,
+    dy: i32,
+    len: u32,
+    variant: TileVariant,
+}
+
+pub(crate) fn serialize<S>(
+    tiles: &HashMap<[i32; 2], TileVariant>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    if serializer.is_human_readable() {
+        return tiles.serialize(serializer);
+    }
+
+    let mut sorted: Vec<_> = tiles.iter().collect();
+    sorted.sort_by_key(|(pos, _)| (pos[1], pos[0]));
+
+    let mut runs: Vec<TileRun> = vec![];
+    let mut cursor = [0, 0];
+    for (pos, variant) in sorted {
+        if let Some(run) = runs.last_mut() {
+            if pos == &cursor && &run.variant == variant {
+                run.len += 1;
+                cursor[0] += 1;
+                continue;
+            }
+        }
+        let row_start = if pos[1] == cursor[1] { cursor[0] } else { 0 };
+        runs.push(TileRun {
+            dx: pos[0] - row_start,
+            dy: pos[1] - cursor[1],
+            len: 1,
+            variant: variant.clone(),
+        });
+        cursor = [pos[0] + 1, pos[1]];
+    }
+    runs.serialize(serializer)
+}
+
+pub(crate) fn deserialize<'de, D>(
+    deserializer: D,
+) -> Result<HashMap<[i32; 2], TileVariant>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    if deserializer.is_human_readable() {
+        return HashMap::deserialize(deserializer);
+    }
+
+    let runs = Vec::<TileRun>::deserialize(deserializer)?;
+    let mut tiles = HashMap::new();
+    let mut cursor = [0i32, 0i32];
+    for TileRun {
+        dx,
+        dy,
+        len,
+        variant,
+    } in runs
+    {
+        if len == 0 || tiles.len() + len as usize > MAX_TILES {
+            return Err(D::Error::custom("invalid tile run length"));
+        }
+        let out_of_range = || D::Error::custom("tile run position out of range");
+        let row_start = if dy == 0 { cursor[0] } else { 0 };
+        let x = row_start.checked_add(dx).ok_or_else(out_of_range)?;
+        let y = cursor[1].checked_add(dy).ok_or_else(out_of_range)?;
+        let end = x.checked_add(len as i32).ok_or_else(out_of_range)?;
+        if x < 0 || end > MAX_COURSE_X || !(0..MAX_COURSE_Y).contains(&y) {
+            return Err(out_of_range());
+        }
+        for x in x..end {
+            tiles.insert([x, y], variant.clone());
+        }
+        cursor = [end, y];
+    }
+    Ok(tiles)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GroundVariant;
+
+    #[derive(Debug, Deserialize, PartialEq, Serialize)]
+    struct Tiles(#[serde(with = "super")] HashMap<[i32; 2], TileVariant>);
+
+    fn tiles() -> Tiles {
+        let mut tiles = HashMap::new();
+        for x in 0..10 {
+            tiles.insert([x, 0], TileVariant::Ground(GroundVariant::default()));
+        }
+        tiles.insert([4, 1], TileVariant::HardBlock);
+        tiles.insert([5, 1], TileVariant::HardBlock);
+        tiles.insert([7, 1], TileVariant::CloudBlock);
+        tiles.insert([0, 5], TileVariant::DonutBlock);
+        Tiles(tiles)
+    }
+
+    fn decode_runs(runs: &[TileRun]) -> postcard::Result<Tiles> {
+        postcard::from_bytes(&postcard::to_allocvec(runs).unwrap())
+    }
+
+    fn run(dx: i32, dy: i32, len: u32) -> TileRun {
+        TileRun {
+            dx,
+            dy,
+            len,
+            variant: TileVariant::HardBlock,
+        }
+    }
+
+    #[test]
+    fn binary_roundtrip() {
+        let tiles = tiles();
+        let buf = postcard::to_allocvec(&tiles).unwrap();
+        assert_eq!(postcard::from_bytes::<Tiles>(&buf).unwrap(), tiles);
+    }
+
+    #[test]
+    fn binary_merges_runs() {
+        let runs: Vec<TileRun> =
+            postcard::from_bytes(&postcard::to_allocvec(&tiles()).unwrap()).unwrap();
+        assert_eq!(runs.len(), 4);
+        assert_eq!(runs[0].len, 10);
+    }
+
+    #[test]
+    fn ron_roundtrip() {
+        let tiles = tiles();
+        let ron = ron::to_string(&tiles).unwrap();
+        assert_eq!(ron::from_str::<Tiles>(&ron).unwrap(), tiles);
+    }
+
+    #[test]
+    fn rejects_zero_length_run() {
+        assert!(decode_runs(&[run(0, 0, 2), run(1, 0, 0)]).is_err());
+    }
+
+    #[test]
+    fn rejects_runs_outside_of_the_limits() {
+        assert!(decode_runs(&[run(MAX_COURSE_X - 1, 0, 2)]).is_err());
+        assert!(decode_runs(&[run(0, MAX_COURSE_Y, 1)]).is_err());
+        assert!(decode_runs(&[run(-1, 0, 1)]).is_err());
+        assert!(decode_runs(&[run(0, 1, 1), run(0, -2, 1)]).is_err());
+        assert!(decode_runs(&[run(i32::MAX, 0, u32::MAX)]).is_err());
+        assert!(decode_runs(&[run(MAX_COURSE_X - 1, MAX_COURSE_Y - 1, 1)]).is_ok());
+    }
+}
@@ -0,0 +1,391 @@
+use crate::{
+    AreaId, AreaPos, Course, CourseArea, TileVariant, FIXED_GROUND_HEIGHT, MAX_COURSE_CELLS,
+    MAX_COURSE_DESCRIPTION_LEN, MAX_COURSE_GOAL_OFFSET_X, MAX_COURSE_TAGS, MAX_COURSE_TAG_LEN,
+    MAX_COURSE_TITLE_LEN, MAX_COURSE_X, MAX_COURSE_Y, MIN_COURSE_X, MIN_COURSE_Y, MIN_GOAL_POS_X,
+    PLAYER_START_OFFSET, START_AREA_WIDTH,
+};
+use enum_iterator::all;
+use std::fmt;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum CourseValidationError {
+    AreaSizeOutOfBounds { area: AreaId },
+    TileOutOfBounds { area: AreaId, pos: [i32; 2] },
+    LinkOutOfBounds { pos: AreaPos },
+    GoalOutOfBounds { goal_pos_x: i32 },
+    StartOutOfBounds { start_pos: [i32; 2] },
+    MissingStartGround { pos: [i32; 2] },
+    MissingGoalGround { pos: [i32; 2] },
+    TitleTooLong,
+    DescriptionTooLong,
+    TooManyTags,
+    TagTooLong { tag: String },
+}
+
+impl fmt::Display for CourseValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::AreaSizeOutOfBounds { area } => write!(
+                f,
+                "size of the {} area must be between {MIN_COURSE_X}x{MIN_COURSE_Y} and \
+                 {MAX_COURSE_X}x{MAX_COURSE_Y} with at most {MAX_COURSE_CELLS} cells",
+                area.get_name()
+            ),
+            Self::TileOutOfBounds { area, pos: [x, y] } => {
+                write!(
+                    f,
+                    "tile at ({x}, {y}) is outside of the {} area",
+                    area.get_name()
+                )
+            }
+            Self::LinkOutOfBounds {
+                pos: AreaPos { area, pos: [x, y] },
+            } => write!(
+                f,
+                "link at ({x}, {y}) is outside of the {} area",
+                area.get_name()
+            ),
+            Self::GoalOutOfBounds { goal_pos_x } => {
+                write!(f, "goal at x = {goal_pos_x} is outside of the main area")
+            }
+            Self::StartOutOfBounds { start_pos: [x, y] } => write!(
+                f,
+                "start at ({x}, {y}) is outside of the main area or overlaps the goal"
+            ),
+            Self::MissingStartGround { pos: [x, y] } => {
+                write!(f, "start ground is missing at ({x}, {y})")
+            }
+            Self::MissingGoalGround { pos: [x, y] } => {
+                write!(f, "goal ground is missing at ({x}, {y})")
+            }
+            Self::TitleTooLong => {
+                write!(f, "title is longer than {MAX_COURSE_TITLE_LEN} characters")
+            }
+            Self::DescriptionTooLong => write!(
+                f,
+                "description is longer than {MAX_COURSE_DESCRIPTION_LEN} characters"
+            ),
+            Self::TooManyTags => write!(f, "course has more than {MAX_COURSE_TAGS} tags"),
+            Self::TagTooLong { tag } => {
+                write!(
+                    f,
+                    "tag \"{tag}\" is longer than {MAX_COURSE_TAG_LEN} characters"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for CourseValidationError {}
+
+impl Course {
+    /// Checks the course against the limits of the editor.
+    ///
+    /// Deserialization only checks the shape of a course,
+    /// so this should be called on every course coming from outside.
+    pub fn validate(&self) -> Result<(), Vec<CourseValidationError>> {
+        let mut errors = vec![];
+
+        let max_goal_pos_x = self
+            .main_area
+            .width
+            .saturating_sub(MAX_COURSE_GOAL_OFFSET_X);
+        let goal_in_bounds = (MIN_GOAL_POS_X..=max_goal_pos_x).contains(&self.goal_pos_x);
+        if !goal_in_bounds {
+            errors.push(CourseValidationError::GoalOutOfBounds {
+                goal_pos_x: self.goal_pos_x,
+            });
+        }
+
+        for area in all::<AreaId>() {
+            let course_area = self.area(area);
+            if !CourseArea::is_valid_size(course_area.width, course_area.height) {
+                errors.push(CourseValidationError::AreaSizeOutOfBounds { area });
+            }
+
+            let max_x = if area == AreaId::Main && goal_in_bounds {
+                self.goal_pos_x.saturating_add(MAX_COURSE_GOAL_OFFSET_X)
+            } else {
+                course_area.width
+            };
+            let mut out_of_bounds: Vec<_> = course_area
+                .tiles
+                .keys()
+                .filter(|[x, y]| *x < 0 || *y < 0 || *x >= max_x || *y >= course_area.height)
+                .copied()
+                .collect();
+            out_of_bounds.sort_by_key(|pos| (pos[1], pos[0]));
+            errors.extend(
+                out_of_bounds
+                    .into_iter()
+                    .map(|pos| CourseValidationError::TileOutOfBounds { area, pos }),
+            );
+        }
+
+        for link in &self.links {
+            for pos in [link.from, link.to] {
+                if !self.area(pos.area).contains(&pos.pos) {
+                    errors.push(CourseValidationError::LinkOutOfBounds { pos });
+                }
+            }
+        }
+
+        let [start_x, start_y] = self.start_pos;
+        // the values come from outside, so they may overflow
+        let player_start_in_bounds = match (
+            start_x.checked_add(PLAYER_START_OFFSET[0]),
+            start_y.checked_add(PLAYER_START_OFFSET[1]),
+        ) {
+            (Some(x), Some(y)) => self.main_area.contains(&[x, y]),
+            _ => false,
+        };
+        let start_in_bounds = start_x >= 0
+            && start_y >= 0
+            && start_x.saturating_add(START_AREA_WIDTH) <= self.goal_pos_x
+            && player_start_in_bounds;
+        if start_in_bounds {
+            for y in start_y..start_y + FIXED_GROUND_HEIGHT {
+                for x in start_x..start_x + START_AREA_WIDTH {
+                    if !self.main_area.is_ground(&[x, y]) {
+                        errors.push(CourseValidationError::MissingStartGround { pos: [x, y] });
+                    }
+                }
+            }
+        } else {
+            errors.push(CourseValidationError::StartOutOfBounds {
+                start_pos: self.start_pos,
+            });
+        }
+        if goal_in_bounds {
+            for y in 0..FIXED_GROUND_HEIGHT {
+                for x in self.goal_pos_x..self.goal_pos_x + MAX_COURSE_GOAL_OFFSET_X {
+                    if !self.main_area.is_ground(&[x, y]) {
+                        errors.push(CourseValidationError::MissingGoalGround { pos: [x, y] });
+                    }
+                }
+            }
+        }
+
+        let metadata = &self.metadata;
+        if metadata.title.chars().count() > MAX_COURSE_TITLE_LEN {
+            errors.push(CourseValidationError::TitleTooLong);
+        }
+        if metadata.description.chars().count() > MAX_COURSE_DESCRIPTION_LEN {
+            errors.push(CourseValidationError::DescriptionTooLong);
+        }
+        if metadata.tags.len() > MAX_COURSE_TAGS {
+            errors.push(CourseValidationError::TooManyTags);
+        }
+        errors.extend(
+            metadata
+                .tags
+                .iter()
+                .filter(|tag| tag.chars().count() > MAX_COURSE_TAG_LEN)
+                .map(|tag| CourseValidationError::TagTooLong { tag: tag.clone() }),
+        );
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+impl CourseArea {
+    fn is_ground(&self, pos: &[i32; 2]) -> bool {
+        matches!(self.tiles.get(pos), Some(TileVariant::Ground(_)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AreaLink, CourseMetadata, GroundVariant, ThemeVariant};
+
+    const GOAL_POS_X: i32 = 50;
+
+    fn course() -> Course {
+        let mut main_area = CourseArea::new(ThemeVariant::Plain);
+        for x in 0..GOAL_POS_X + MAX_COURSE_GOAL_OFFSET_X {
+            for y in 0..FIXED_GROUND_HEIGHT {
+                main_area
+                    .tiles
+                    .insert([x, y], TileVariant::Ground(GroundVariant::default()));
+            }
+        }
+        Course {
+            main_area,
+            sub_area: CourseArea::new(ThemeVariant::Underground),
+            links: vec![],
+            start_pos: [0, 0],
+            goal_pos_x: GOAL_POS_X,
+            metadata: CourseMetadata::default(),
+        }
+    }
+
+    fn assert_invalid(course: Course, error: CourseValidationError) {
+        assert_eq!(course.validate(), Err(vec![error]));
+    }
+
+    #[test]
+    fn valid_course() {
+        assert_eq!(course().validate(), Ok(()));
+    }
+
+    #[test]
+    fn area_size_out_of_bounds() {
+        let mut course = course();
+        course.sub_area.width = MIN_COURSE_X - 1;
+        assert_invalid(
+            course,
+            CourseValidationError::AreaSizeOutOfBounds { area: AreaId::Sub },
+        );
+    }
+
+    #[test]
+    fn tile_out_of_bounds() {
+        let mut course = course();
+        course
+            .sub_area
+            .tiles
+            .insert([-1, 3], TileVariant::HardBlock);
+        assert_invalid(
+            course,
+            CourseValidationError::TileOutOfBounds {
+                area: AreaId::Sub,
+                pos: [-1, 3],
+            },
+        );
+    }
+
+    #[test]
+    fn tile_behind_the_goal() {
+        let mut course = course();
+        let pos = [GOAL_POS_X + MAX_COURSE_GOAL_OFFSET_X, 3];
+        course.main_area.tiles.insert(pos, TileVariant::HardBlock);
+        assert_invalid(
+            course,
+            CourseValidationError::TileOutOfBounds {
+                area: AreaId::Main,
+                pos,
+            },
+        );
+    }
+
+    #[test]
+    fn link_out_of_bounds() {
+        let mut course = course();
+        let to = AreaPos {
+            area: AreaId::Sub,
+            pos: [1000, 0],
+        };
+        course.links.push(AreaLink {
+            from: AreaPos {
+                area: AreaId::Main,
+                pos: [1, 1],
+            },
+            to,
+        });
+        assert_invalid(course, CourseValidationError::LinkOutOfBounds { pos: to });
+    }
+
+    #[test]
+    fn goal_out_of_bounds() {
+        let mut course = course();
+        course.goal_pos_x = MIN_GOAL_POS_X - 1;
+        assert_invalid(
+            course,
+            CourseValidationError::GoalOutOfBounds {
+                goal_pos_x: MIN_GOAL_POS_X - 1,
+            },
+        );
+    }
+
+    #[test]
+    fn start_out_of_bounds() {
+        let mut course = course();
+        course.start_pos = [GOAL_POS_X, 0];
+        assert_invalid(
+            course,
+            CourseValidationError::StartOutOfBounds {
+                start_pos: [GOAL_POS_X, 0],
+            },
+        );
+    }
+
+    #[test]
+    fn missing_start_ground() {
+        let mut course = course();
+        course.main_area.tiles.remove(&[3, 1]);
+        assert_invalid(
+            course,
+            CourseValidationError::MissingStartGround { pos: [3, 1] },
+        );
+    }
+
+    #[test]
+    fn missing_goal_ground() {
+        let mut course = course();
+        course.main_area.tiles.remove(&[GOAL_POS_X + 1, 0]);
+        assert_invalid(
+            course,
+            CourseValidationError::MissingGoalGround {
+                pos: [GOAL_POS_X + 1, 0],
+            },
+        );
+    }
+
+    #[test]
+    fn title_too_long() {
+        let mut course = course();
+        course.metadata.title = "a".repeat(MAX_COURSE_TITLE_LEN + 1);
+        assert_invalid(course, CourseValidationError::TitleTooLong);
+    }
+
+    #[test]
+    fn description_too_long() {
+        let mut course = course();
+        course.metadata.description = "a".repeat(MAX_COURSE_DESCRIPTION_LEN + 1);
+        assert_invalid(course, CourseValidationError::DescriptionTooLong);
+    }
+
+    #[test]
+    fn too_many_tags() {
+        let mut course = course();
+        course.metadata.tags = vec!["tag".to_string(); MAX_COURSE_TAGS + 1];
+        assert_invalid(course, CourseValidationError::TooManyTags);
+    }
+
+    #[test]
+    fn tag_too_long() {
+        let mut course = course();
+        let tag = "a".repeat(MAX_COURSE_TAG_LEN + 1);
+        course.metadata.tags = vec![tag.clone()];
+        assert_invalid(course, CourseValidationError::TagTooLong { tag });
+    }
+
+    #[test]
+    fn extreme_values_do_not_overflow() {
+        for value in [i32::MIN, i32::MAX] {
+            let mut extreme = course();
+            extreme.main_area.width = value;
+            extreme.main_area.height = value;
+            extreme.goal_pos_x = value;
+            extreme.start_pos = [value, value];
+            extreme
+                .main_area
+                .tiles
+                .insert([value, value], TileVariant::HardBlock);
+            assert!(extreme.validate().is_err());
+
+            let mut extreme = course();
+            extreme.goal_pos_x = value;
+            extreme.start_pos = [value, value];
+            assert!(extreme.validate().is_err());
+        }
+        let mut extreme = course();
+        extreme.start_pos = [i32::MAX - START_AREA_WIDTH / 2, 0];
+        assert!(extreme.validate().is_err());
+    }
+}
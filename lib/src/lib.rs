@@ -1,7 +1,15 @@
+mod autotile;
 mod course;
+mod limits;
+mod replay;
+mod shape;
 mod theme;
 mod tile;
 
+pub use autotile::*;
 pub use course::*;
+pub use limits::*;
+pub use replay::*;
+pub use shape::*;
 pub use theme::*;
 pub use tile::*;
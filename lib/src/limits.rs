@@ -0,0 +1,27 @@
+pub const MAX_COURSE_GOAL_OFFSET_X: i32 = 16;
+pub const MIN_GOAL_POS_X: i32 = 32;
+
+/// Size of new course areas.
+pub const DEFAULT_COURSE_X: i32 = 150;
+pub const DEFAULT_COURSE_Y: i32 = 24;
+pub const MIN_COURSE_X: i32 = MIN_GOAL_POS_X + MAX_COURSE_GOAL_OFFSET_X;
+pub const MAX_COURSE_X: i32 = 500;
+pub const MIN_COURSE_Y: i32 = 16;
+pub const MAX_COURSE_Y: i32 = 250;
+/// Maximum number of grid cells of a course area, which keeps tall courses from also being wide.
+pub const MAX_COURSE_CELLS: i32 = 12_000;
+
+/// Position the player spawns at, relative to the start position of a course.
+pub const PLAYER_START_OFFSET: [i32; 2] = [5, 6];
+/// Width of the non-editable ground the player starts on.
+pub const START_AREA_WIDTH: i32 = 8;
+/// Height of the non-editable ground at the start and at the goal.
+pub const FIXED_GROUND_HEIGHT: i32 = 2;
+
+/// Maximum number of characters of a course title.
+pub const MAX_COURSE_TITLE_LEN: usize = 64;
+/// Maximum number of characters of a course description.
+pub const MAX_COURSE_DESCRIPTION_LEN: usize = 512;
+pub const MAX_COURSE_TAGS: usize = 8;
+/// Maximum number of characters of a single course tag.
+pub const MAX_COURSE_TAG_LEN: usize = 24;
@@ -0,0 +1,182 @@
+use crate::Course;
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+
+/// Magic bytes every replay file starts with.
+pub const REPLAY_MAGIC: [u8; 4] = *b"SHRR";
+
+/// Format version written by [`Replay::serialize`].
+///
+/// The course is stored with its own format version, so this only covers the replay itself.
+pub const REPLAY_FORMAT_VERSION: u16 = 1;
+
+/// The actions the player consumes during one frame.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct InputFrame {
+    pub left: bool,
+    pub right: bool,
+    pub up: bool,
+    pub down: bool,
+    pub run: bool,
+    pub jump: bool,
+}
+
+impl InputFrame {
+    fn to_bits(self) -> u8 {
+        [
+            self.left, self.right, self.up, self.down, self.run, self.jump,
+        ]
+        .into_iter()
+        .enumerate()
+        .fold(0, |bits, (bit, is_set)| bits | ((is_set as u8) << bit))
+    }
+
+    fn from_bits(bits: u8) -> Self {
+        let is_set = |bit: u8| bits & (1 << bit) != 0;
+        Self {
+            left: is_set(0),
+            right: is_set(1),
+            up: is_set(2),
+            down: is_set(3),
+            run: is_set(4),
+            jump: is_set(5),
+        }
+    }
+}
+
+/// A recorded run: the course it was played on and the input of every frame.
+#[derive(Clone, Debug)]
+pub struct Replay {
+    pub course: Course,
+    pub frames: Vec<InputFrame>,
+}
+
+#[derive(Deserialize, Serialize)]
+struct ReplayFile {
+    /// The course as written by [`Course::serialize`].
+    course: Vec<u8>,
+    /// One [`InputFrame`] per byte.
+    frames: Vec<u8>,
+}
+
+impl Replay {
+    pub fn serialize(&self) -> Result<Vec<u8>> {
+        let file = ReplayFile {
+            course: self.course.serialize()?,
+            frames: self.frames.iter().map(|frame| frame.to_bits()).collect(),
+        };
+        let mut buf = vec![];
+        buf.extend_from_slice(&REPLAY_MAGIC);
+        buf.extend_from_slice(&REPLAY_FORMAT_VERSION.to_le_bytes());
+        buf.extend(postcard::to_allocvec(&file)?);
+        Ok(buf)
+    }
+
+    /// Reads a replay written by [`Replay::serialize`].
+    ///
+    /// The course is validated like a course file, since it is loaded into the editor as is.
+    pub fn deserialize(buf: &[u8]) -> Result<Self> {
+        let payload = if let Some(payload) = buf.strip_prefix(&REPLAY_MAGIC) {
+            payload
+        } else {
+            bail!("not a replay file");
+        };
+        if payload.len() < std::mem::size_of::<u16>() {
+            bail!("replay header is truncated");
+        }
+        let (version, payload) = payload.split_at(std::mem::size_of::<u16>());
+        let version = u16::from_le_bytes([version[0], version[1]]);
+        if version != REPLAY_FORMAT_VERSION {
+            bail!("unknown replay format version {version}");
+        }
+        let file: ReplayFile = postcard::from_bytes(payload)?;
+        let course = Course::deserialize(file.course)?;
+        if let Err(errors) = course.validate() {
+            bail!(
+                "course of the replay is invalid:\n{}",
+                errors
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            );
+        }
+        Ok(Self {
+            course,
+            frames: file.frames.into_iter().map(InputFrame::from_bits).collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        CourseArea, CourseMetadata, GroundVariant, ThemeVariant, TileVariant, FIXED_GROUND_HEIGHT,
+        MAX_COURSE_GOAL_OFFSET_X,
+    };
+
+    fn replay() -> Replay {
+        let mut main_area = CourseArea::new(ThemeVariant::Plain);
+        for x in 0..50 + MAX_COURSE_GOAL_OFFSET_X {
+            for y in 0..FIXED_GROUND_HEIGHT {
+                main_area
+                    .tiles
+                    .insert([x, y], TileVariant::Ground(GroundVariant::default()));
+            }
+        }
+        Replay {
+            course: Course {
+                main_area,
+                sub_area: CourseArea::new(ThemeVariant::Underground),
+                links: vec![],
+                start_pos: [0, 0],
+                goal_pos_x: 50,
+                metadata: CourseMetadata::default(),
+            },
+            frames: vec![
+                InputFrame::default(),
+                InputFrame {
+                    right: true,
+                    run: true,
+                    ..Default::default()
+                },
+                InputFrame {
+                    left: true,
+                    up: true,
+                    down: true,
+                    jump: true,
+                    ..Default::default()
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn replay_roundtrip() {
+        let replay = replay();
+        let loaded = Replay::deserialize(&replay.serialize().unwrap()).unwrap();
+        assert_eq!(loaded.frames, replay.frames);
+        assert_eq!(loaded.course.goal_pos_x, replay.course.goal_pos_x);
+    }
+
+    #[test]
+    fn replay_rejects_unknown_version() {
+        let mut buf = replay().serialize().unwrap();
+        buf[REPLAY_MAGIC.len()..REPLAY_MAGIC.len() + 2]
+            .copy_from_slice(&(REPLAY_FORMAT_VERSION + 1).to_le_bytes());
+        assert!(Replay::deserialize(&buf).is_err());
+        assert!(Replay::deserialize(b"SHRM").is_err());
+    }
+
+    #[test]
+    fn replay_rejects_invalid_course() {
+        let mut replay = replay();
+        replay.course.goal_pos_x = i32::MAX;
+        replay.course.main_area.width = i32::MAX;
+        let err = Replay::deserialize(&replay.serialize().unwrap()).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("course of the replay is invalid"));
+    }
+}
@@ -0,0 +1,126 @@
+//! Grid shapes drawn by the placement tools of the editor.
+
+use std::collections::{HashMap, HashSet};
+
+/// Returns the cells of a line from `from` to `to`, both included.
+pub fn line(from: [i32; 2], to: [i32; 2]) -> Vec<[i32; 2]> {
+    let [mut x, mut y] = from;
+    let dx = (to[0] - x).abs();
+    let dy = -(to[1] - y).abs();
+    let step_x = (to[0] - x).signum();
+    let step_y = (to[1] - y).signum();
+    let mut err = dx + dy;
+
+    let mut cells = vec![];
+    loop {
+        cells.push([x, y]);
+        if [x, y] == to {
+            return cells;
+        }
+        let err2 = 2 * err;
+        if err2 >= dy {
+            err += dy;
+            x += step_x;
+        }
+        if err2 <= dx {
+            err += dx;
+            y += step_y;
+        }
+    }
+}
+
+/// Returns the cells of the rectangle spanned by the corners `from` and `to`.
+///
+/// A hollow rectangle only contains its border.
+pub fn rect(from: [i32; 2], to: [i32; 2], filled: bool) -> Vec<[i32; 2]> {
+    let (min_x, max_x) = (from[0].min(to[0]), from[0].max(to[0]));
+    let (min_y, max_y) = (from[1].min(to[1]), from[1].max(to[1]));
+    (min_y..=max_y)
+        .flat_map(|y| (min_x..=max_x).map(move |x| [x, y]))
+        .filter(|&[x, y]| filled || x == min_x || x == max_x || y == min_y || y == max_y)
+        .collect()
+}
+
+/// Returns the empty cells connected to `start` that lie within `width` and `height`.
+///
+/// Returns nothing if `start` is not empty.
+pub fn flood_fill<T>(
+    tiles: &HashMap<[i32; 2], T>,
+    start: [i32; 2],
+    width: i32,
+    height: i32,
+) -> Vec<[i32; 2]> {
+    let is_free = |[x, y]: [i32; 2]| {
+        (0..width).contains(&x) && (0..height).contains(&y) && !tiles.contains_key(&[x, y])
+    };
+    if !is_free(start) {
+        return vec![];
+    }
+
+    let mut visited = HashSet::from([start]);
+    let mut cells = vec![];
+    let mut stack = vec![start];
+    while let Some(cell @ [x, y]) = stack.pop() {
+        cells.push(cell);
+        for next in [[x - 1, y], [x + 1, y], [x, y - 1], [x, y + 1]] {
+            if is_free(next) && visited.insert(next) {
+                stack.push(next);
+            }
+        }
+    }
+    cells
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn line_connects_end_points() {
+        for to in [[5, 0], [0, 5], [-5, 3], [4, -7], [3, 3], [0, 0]] {
+            let cells = line([0, 0], to);
+            assert_eq!(cells.first(), Some(&[0, 0]));
+            assert_eq!(cells.last(), Some(&to));
+            assert_eq!(
+                cells.len() as i32,
+                to[0].abs().max(to[1].abs()) + 1,
+                "to {to:?}"
+            );
+            for pair in cells.windows(2) {
+                let [[x0, y0], [x1, y1]] = [pair[0], pair[1]];
+                assert!((x1 - x0).abs() <= 1 && (y1 - y0).abs() <= 1, "to {to:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn rect_ignores_corner_order() {
+        let mut a = rect([1, 1], [3, 4], true);
+        let mut b = rect([3, 4], [1, 1], true);
+        a.sort();
+        b.sort();
+        assert_eq!(a, b);
+        assert_eq!(a.len(), 12);
+    }
+
+    #[test]
+    fn hollow_rect_is_border() {
+        assert_eq!(rect([0, 0], [3, 3], false).len(), 12);
+        assert_eq!(rect([0, 0], [3, 0], false).len(), 4);
+        assert_eq!(rect([2, 2], [2, 2], false), vec![[2, 2]]);
+    }
+
+    #[test]
+    fn flood_fill_stops_at_tiles_and_bounds() {
+        // a wall at x = 2 splits the 5x3 area
+        let tiles: HashMap<_, _> = (0..3).map(|y| ([2, y], ())).collect();
+        let mut cells = flood_fill(&tiles, [0, 1], 5, 3);
+        let mut expected = rect([0, 0], [1, 2], true);
+        cells.sort();
+        expected.sort();
+        assert_eq!(cells, expected);
+        assert_eq!(flood_fill(&tiles, [4, 0], 5, 3).len(), 6);
+        assert!(flood_fill(&tiles, [2, 0], 5, 3).is_empty());
+        assert!(flood_fill(&tiles, [5, 0], 5, 3).is_empty());
+    }
+}
@@ -85,7 +85,34 @@ pub enum GroundVariant {
     CornerBR,
 }
 
+/// Tab of the editor palette a tile is listed in.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq, Sequence)]
+pub enum TileCategory {
+    #[default]
+    Terrain,
+    Blocks,
+    Gizmos,
+}
+
 impl TileVariant {
+    /// Every tile the editor offers, in palette order.
+    ///
+    /// Ground is only listed once, with its default variant, as it is autotiled when placed.
+    pub fn palette() -> impl Iterator<Item = Self> {
+        enum_iterator::all::<Self>().filter(|tile| match tile {
+            Self::Ground(variant) => *variant == GroundVariant::default(),
+            _ => true,
+        })
+    }
+
+    pub fn category(&self) -> TileCategory {
+        match self {
+            Self::Ground(_) => TileCategory::Terrain,
+            Self::HardBlock | Self::RotatingBlock | Self::CloudBlock => TileCategory::Blocks,
+            Self::DonutBlock => TileCategory::Gizmos,
+        }
+    }
+
     pub fn get_sprite_sheet_index(&self) -> usize {
         match self {
             Self::Ground(variant) => variant.get_sprite_sheet_index(),
@@ -622,3 +649,29 @@ impl GroundVariant {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn palette_lists_every_kind_once() {
+        let palette: Vec<_> = TileVariant::palette().collect();
+        assert_eq!(
+            palette,
+            vec![
+                TileVariant::Ground(GroundVariant::default()),
+                TileVariant::HardBlock,
+                TileVariant::RotatingBlock,
+                TileVariant::DonutBlock,
+                TileVariant::CloudBlock,
+            ]
+        );
+        for category in enum_iterator::all::<TileCategory>() {
+            assert!(
+                palette.iter().any(|tile| tile.category() == category),
+                "{category:?} is empty"
+            );
+        }
+    }
+}